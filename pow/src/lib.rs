@@ -0,0 +1,116 @@
+//! Proof of work of the Namada faucet.
+//!
+//! A solution to a challenge is valid at difficulty `d` when
+//! `sha256(challenge || solution)` starts with `d` zero hex digits. Both the
+//! challenge and the solution travel as lowercase hex. The crate is `no_std`
+//! so the exact same code runs in the server and, through the bindings in
+//! `pow/wasm`, in browsers.
+#![no_std]
+
+use sha2::{Digest, Sha256};
+
+/// Length of a SHA-256 hash in hex digits, the highest meaningful difficulty.
+pub const MAX_DIFFICULTY: u64 = 64;
+
+/// Checks `solution` against `challenge`, both raw bytes.
+pub fn is_valid(challenge: &[u8], solution: &[u8], difficulty: u64) -> bool {
+    let hash = Sha256::new()
+        .chain_update(challenge)
+        .chain_update(solution)
+        .finalize();
+
+    leading_zero_nibbles(&hash) >= difficulty.min(MAX_DIFFICULTY)
+}
+
+/// Checks `solution` against `challenge`, both lowercase hex. Uppercase or
+/// odd length input is invalid.
+pub fn is_valid_hex(challenge: &str, solution: &str, difficulty: u64) -> bool {
+    let mut hasher = Sha256::new();
+    if !update_hex(&mut hasher, challenge) || !update_hex(&mut hasher, solution) {
+        return false;
+    }
+
+    leading_zero_nibbles(&hasher.finalize()) >= difficulty.min(MAX_DIFFICULTY)
+}
+
+/// Tries each of `nonces` until one solves `challenge`. Solutions are the 8
+/// byte big endian nonce. Workers split the search by taking different
+/// nonces, e.g. `(worker..).step_by(workers)`.
+pub fn solve(
+    challenge: &[u8],
+    difficulty: u64,
+    nonces: impl IntoIterator<Item = u64>,
+) -> Option<[u8; 8]> {
+    let prefix = Sha256::new().chain_update(challenge);
+    nonces.into_iter().map(u64::to_be_bytes).find(|solution| {
+        let hash = prefix.clone().chain_update(solution).finalize();
+        leading_zero_nibbles(&hash) >= difficulty.min(MAX_DIFFICULTY)
+    })
+}
+
+/// Number of leading zero hex digits of `bytes`.
+pub fn leading_zero_nibbles(bytes: &[u8]) -> u64 {
+    let mut nibbles = 0;
+    for byte in bytes {
+        if byte & 0xf0 != 0 {
+            break;
+        }
+        nibbles += 1;
+        if byte & 0x0f != 0 {
+            break;
+        }
+        nibbles += 1;
+    }
+
+    nibbles
+}
+
+/// Decodes lowercase hex into `out`, returning the number of bytes written,
+/// or `None` if the input isn't lowercase hex or doesn't fit.
+pub fn decode_hex(hex: &str, out: &mut [u8]) -> Option<usize> {
+    let hex = hex.as_bytes();
+    if hex.len() % 2 != 0 || hex.len() / 2 > out.len() {
+        return None;
+    }
+    for (byte, pair) in out.iter_mut().zip(hex.chunks_exact(2)) {
+        *byte = (nibble(pair[0])? << 4) | nibble(pair[1])?;
+    }
+
+    Some(hex.len() / 2)
+}
+
+/// Encodes `bytes` as lowercase hex into `out`, which must be twice as long.
+pub fn encode_hex<'a>(bytes: &[u8], out: &'a mut [u8]) -> Option<&'a str> {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    let out = out.get_mut(..bytes.len() * 2)?;
+    for (pair, byte) in out.chunks_exact_mut(2).zip(bytes) {
+        pair[0] = DIGITS[(byte >> 4) as usize];
+        pair[1] = DIGITS[(byte & 0x0f) as usize];
+    }
+
+    core::str::from_utf8(out).ok()
+}
+
+fn update_hex(hasher: &mut Sha256, hex: &str) -> bool {
+    let mut buffer = [0; 64];
+    for chunk in hex.as_bytes().chunks(buffer.len() * 2) {
+        let Ok(chunk) = core::str::from_utf8(chunk) else {
+            return false;
+        };
+        match decode_hex(chunk, &mut buffer) {
+            Some(len) => hasher.update(&buffer[..len]),
+            None => return false,
+        }
+    }
+
+    true
+}
+
+fn nibble(digit: u8) -> Option<u8> {
+    match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        _ => None,
+    }
+}
@@ -0,0 +1,45 @@
+use namada_faucet_pow::{decode_hex, encode_hex, is_valid_hex, solve};
+use serde::Deserialize;
+
+/// The vectors the server is tested against, see `tests/vectors`.
+#[derive(Deserialize)]
+struct PowVector {
+    challenge: String,
+    solution: String,
+    difficulty: u64,
+    valid: bool,
+}
+
+fn vectors() -> Vec<PowVector> {
+    serde_json::from_str(include_str!("../../tests/vectors/pow.json")).unwrap()
+}
+
+#[test]
+fn verifies_the_vectors() {
+    for vector in vectors() {
+        assert_eq!(
+            is_valid_hex(&vector.challenge, &vector.solution, vector.difficulty),
+            vector.valid,
+            "{} {} at difficulty {}",
+            vector.challenge,
+            vector.solution,
+            vector.difficulty
+        );
+    }
+}
+
+#[test]
+fn finds_the_vector_solutions() {
+    for vector in vectors() {
+        if !vector.valid || vector.difficulty == 0 {
+            continue;
+        }
+
+        let mut challenge = [0; 16];
+        let len = decode_hex(&vector.challenge, &mut challenge).unwrap();
+        let solution = solve(&challenge[..len], vector.difficulty, 0..).unwrap();
+
+        let mut hex = [0; 16];
+        assert_eq!(encode_hex(&solution, &mut hex).unwrap(), vector.solution);
+    }
+}
@@ -0,0 +1,29 @@
+//! JavaScript bindings to `namada-faucet-pow`, built for browsers with
+//! `wasm-pack build pow/wasm --target web`.
+
+use wasm_bindgen::prelude::*;
+
+/// Checks a hex encoded solution, exactly like the faucet does.
+#[wasm_bindgen(js_name = isValid)]
+pub fn is_valid(challenge: &str, solution: &str, difficulty: u32) -> bool {
+    namada_faucet_pow::is_valid_hex(challenge, solution, difficulty as u64)
+}
+
+/// Solves a hex encoded challenge, returning the hex encoded solution.
+/// `start` and `step` split the search between web workers, pass 0 and 1
+/// when solving on a single thread.
+#[wasm_bindgen]
+pub fn solve(challenge: &str, difficulty: u32, start: u32, step: u32) -> Result<String, JsError> {
+    let mut buffer = [0; 64];
+    let len = namada_faucet_pow::decode_hex(challenge, &mut buffer)
+        .ok_or_else(|| JsError::new("challenge must be lowercase hex of at most 64 bytes"))?;
+
+    let nonces = (start as u64..).step_by(step.max(1) as usize);
+    let solution = namada_faucet_pow::solve(&buffer[..len], difficulty as u64, nonces)
+        .ok_or_else(|| JsError::new("no solution found"))?;
+
+    let mut hex = [0; 16];
+    namada_faucet_pow::encode_hex(&solution, &mut hex)
+        .map(str::to_string)
+        .ok_or_else(|| JsError::new("can't encode the solution"))
+}
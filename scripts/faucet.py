@@ -10,46 +10,45 @@ def request_challenge(url):
 def request_transfer(url, data):
     return post(url, json=data)
 
-def is_valid_pow(solution, difficulty):
-    for i in range(0, difficulty):
-        if solution[i] != 0:
-            return False
-    return True
+def is_valid_pow(hash, difficulty):
+    # The first `difficulty` hex digits of the hash must be zero, see
+    # tests/vectors/pow.json.
+    return hash.hex()[:difficulty] == '0' * difficulty
 
 def compute_pow_solution(challenge, difficulty):
     challenge_bytes = bytes.fromhex(challenge)
     i = 0
-    while i >= 0:
+    while True:
+        solution = i.to_bytes(8, byteorder='big')
         hasher = sha256()
         hasher.update(challenge_bytes)
-        hasher.update(i.to_bytes(64, byteorder='big'))
-        hash = hasher.digest()
+        hasher.update(solution)
 
-        valid = is_valid_pow(hash, difficulty)
-        if valid:
-            return i.to_bytes(64, byteorder='big').hex()
+        if is_valid_pow(hasher.digest(), difficulty):
+            return solution.hex()
         i += 1
 
 if __name__ == '__main__':
     parser = argparse.ArgumentParser(description='Request from an amount of token from faucet.')
-    parser.add_argument('url', action='store', type=str, required=False, default=DEFAULT_URL, help='The faucet url.')
-    parser.add_argument('token', action='store', type=int, required=False, help='The token address.')
-    parser.add_argument('amount', action='store', type=int, required=False, default=1000, help='The token amount.')
-    parser.add_argument('target', action='store', type=str, required=True, help='The target address.')
+    parser.add_argument('target', type=str, help='The target address.')
+    parser.add_argument('token', type=str, help='The token address.')
+    parser.add_argument('--amount', type=int, default=1000, help='The token amount, in whole tokens.')
+    parser.add_argument('--url', type=str, default=DEFAULT_URL, help='The faucet url.')
 
     args = parser.parse_args()
 
+    difficulty = get(args.url + '/setting').json()['difficulty']
     response = request_challenge(args.url)
-    solution = compute_pow_solution(args.url, response['challenge'], 2)
-    response = request_transfer({
+    solution = compute_pow_solution(response['challenge'], difficulty)
+    response = request_transfer(args.url, {
         'solution': solution,
         'tag': response['tag'],
         'challenge': response['challenge'],
         'transfer': {
             'target': args.target,
             'token': args.token,
-            'amount': args.amounr * 10**6
+            'amount': args.amount * 10**6
         }
     })
 
-    print(response.json())
\ No newline at end of file
+    print(response.json())
@@ -1,35 +1,46 @@
 use std::{
     net::{Ipv4Addr, SocketAddr},
-    str::FromStr,
     sync::Arc,
     time::Duration,
 };
 
 use tokio::{sync::RwLock, time::sleep};
 
+use anyhow::Context;
 use axum::{
     error_handling::HandleErrorLayer,
     http::{HeaderValue, StatusCode},
+    middleware,
     response::IntoResponse,
     routing::{get, post},
     BoxError, Json, Router,
 };
 use lazy_static::lazy_static;
-use namada_sdk::{
-    address::Address, args::TxBuilder, chain::ChainId, io::NullIo, key::RefTo,
-    masp::fs::FsShieldedUtils, wallet::fs::FsWalletUtils, NamadaImpl,
-};
+use namada_sdk::{address::Address, key::RefTo};
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use serde_json::json;
-use tendermint_rpc::{HttpClient, Url};
-use tower::{buffer::BufferLayer, limit::RateLimitLayer, ServiceBuilder};
+use tower::{buffer::BufferLayer, ServiceBuilder};
 use tower_http::{
     cors::{Any, CorsLayer},
     trace::TraceLayer,
 };
 
-use crate::{app_state::AppState, config::AppConfig, state::faucet::FaucetState};
-use crate::{handler::faucet as faucet_handler, sdk::utils::sk_from_str};
+use crate::{
+    app_state::AppState,
+    config::Config,
+    reload::ConfigReloader,
+    sdk::{
+        light_client::LightClientVerifier,
+        namada::{self, NamadaClient},
+    },
+    services::treasury::TreasuryService,
+    state::faucet::{FaucetSettings, FaucetState},
+    utils::rate_limit::RateLimiter,
+};
+use crate::{
+    handler::admin as admin_handler, handler::faucet as faucet_handler,
+    handler::metrics as metrics_handler, handler::openapi as openapi_handler,
+};
 
 lazy_static! {
     static ref HTTP_TIMEOUT: u64 = 30;
@@ -39,7 +50,7 @@ lazy_static! {
 pub struct ApplicationServer;
 
 impl ApplicationServer {
-    pub async fn serve(config: Arc<AppConfig>, db: Arc<RwLock<AppState>>) -> anyhow::Result<()> {
+    pub async fn serve(config: Arc<Config>, db: Arc<RwLock<AppState>>) -> anyhow::Result<()> {
         let auth_key = config.auth_key.clone();
         let auth_key = auth_key.unwrap_or_else(|| {
             thread_rng()
@@ -49,19 +60,22 @@ impl ApplicationServer {
                 .collect()
         });
 
-        assert!(auth_key.len() == 32);
-
-        let difficulty = config.difficulty;
         let rps = config.rps;
         let chain_id = config.chain_id.clone();
-        let rpc = config.rpc.clone();
         let chain_start = config.chain_start;
-        let withdraw_limit = config.withdraw_limit.unwrap_or(1000_u64);
+        let admin_key = config.admin_key.clone();
+        let access_list_path = config.access_list_path.clone();
+        let settings = Arc::new(RwLock::new(FaucetSettings::from(config.as_ref())));
 
-        let sk = config.private_key.clone();
-        let sk = sk_from_str(&sk);
-        let pk = sk.ref_to();
-        let address = Address::from(&pk);
+        {
+            let mut state = db.write().await;
+            state.access_list = config.access_list.clone();
+            state.api_keys = config
+                .api_keys
+                .iter()
+                .map(|api_key| (api_key.key_hash.clone(), api_key.clone()))
+                .collect();
+        }
 
         loop {
             let current_timestamp = chrono::offset::Utc::now().timestamp();
@@ -73,54 +87,94 @@ impl ApplicationServer {
             }
         }
 
-        let url = Url::from_str(&rpc).expect("invalid RPC address");
-        let http_client = HttpClient::new(url).unwrap();
+        let (sdk, accounts) = namada::init(&config).await?;
 
-        // Setup wallet storage
-        let wallet = FsWalletUtils::new("wallet".into());
+        let light_client = match &config.trust_root {
+            Some(trust_root) => Some(
+                LightClientVerifier::new(&config.rpc, trust_root)
+                    .await
+                    .context("unable to start the light client")?,
+            ),
+            None => None,
+        };
 
-        // Setup shielded context storage
-        let shielded_ctx = FsShieldedUtils::new("masp".into());
+        let treasury = config
+            .refill_policy
+            .as_ref()
+            .map(|policy| Address::from(&policy.treasury_key.ref_to()));
 
-        let null_io = NullIo;
+        let faucet_state = FaucetState::new(
+            &db,
+            accounts,
+            treasury,
+            Arc::new(NamadaClient::new(Arc::new(sdk), light_client)),
+            auth_key,
+            admin_key,
+            access_list_path,
+            &settings,
+            chain_id.to_string(),
+            chain_start,
+            config.dry_run,
+        );
 
-        let sdk = NamadaImpl::new(http_client, wallet, shielded_ctx, null_io)
-            .await
-            .expect("unable to initialize Namada context")
-            .chain_id(ChainId::from_str(&chain_id).unwrap());
-
-        let mut wallet = sdk.wallet.write().await;
-        wallet
-            .insert_keypair(
-                "faucet".to_string(),
-                true,
-                sk.clone(),
-                None,
-                Some(address.clone()),
-                None,
+        if let Some(policy) = &config.refill_policy {
+            TreasuryService::new(
+                faucet_state.ledger.clone(),
+                faucet_state.accounts.clone(),
+                &settings,
+                policy.clone(),
             )
-            .unwrap();
-
-        drop(wallet);
-
-        let routes = {
-            let faucet_state = FaucetState::new(
-                &db,
-                address,
-                sdk,
-                auth_key,
-                difficulty,
-                chain_id,
-                chain_start,
-                withdraw_limit,
-            );
+            .spawn();
+        }
 
-            Router::new()
-                .route("/faucet/setting", get(faucet_handler::faucet_settings))
-                .route("/faucet", get(faucet_handler::request_challenge))
-                .route("/faucet", post(faucet_handler::request_transfer))
-                .with_state(faucet_state)
-        };
+        let rate_limiter = RateLimiter::new(rps.unwrap_or(*REQ_PER_SEC));
+
+        ConfigReloader::new(config.as_ref().clone(), &settings, &db, &rate_limiter)
+            .spawn()
+            .context("unable to watch configuration")?;
+
+        let router = Self::router(faucet_state, rate_limiter);
+
+        let port = config.port;
+        let addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, port));
+
+        if config.dry_run {
+            tracing::warn!("Running in dry run mode, transfers are never broadcast");
+        }
+
+        tracing::info!("🚀 Server has launched on https://{addr}");
+
+        axum::Server::bind(&addr)
+            .serve(router.into_make_service_with_connect_info::<SocketAddr>())
+            .with_graceful_shutdown(Self::shutdown_signal())
+            .await
+            .unwrap_or_else(|e| panic!("Server error: {}", e));
+
+        Ok(())
+    }
+
+    /// Builds the application routes and middleware around `faucet_state`
+    /// without binding anything, so that it can also be driven directly.
+    pub fn router(faucet_state: FaucetState, rate_limiter: RateLimiter) -> Router {
+        let routes = Router::new()
+            .route("/faucet/setting", get(faucet_handler::faucet_settings))
+            .route("/faucet", get(faucet_handler::request_challenge))
+            .route("/faucet", post(faucet_handler::request_transfer))
+            .route(
+                "/faucet/events",
+                get(faucet_handler::idempotent_transfer_events),
+            )
+            .route(
+                "/faucet/events/:request_id",
+                get(faucet_handler::transfer_events),
+            )
+            .route(
+                "/admin/access-list",
+                get(admin_handler::get_access_list).put(admin_handler::update_access_list),
+            )
+            .route("/admin/accounts", get(admin_handler::get_accounts))
+            .route("/openapi.json", get(openapi_handler::openapi))
+            .with_state(faucet_state.clone());
 
         let cors = CorsLayer::new()
             .allow_origin("*".parse::<HeaderValue>().unwrap())
@@ -129,6 +183,11 @@ impl ApplicationServer {
 
         let router = Router::new()
             .nest("/api/v1", routes)
+            .merge(
+                Router::new()
+                    .route("/metrics", get(metrics_handler::metrics))
+                    .with_state(faucet_state),
+            )
             .merge(Router::new().route(
                 "/health",
                 get(|| async { json!({"commit": env!("VERGEN_GIT_SHA").to_string(), "version": env!("CARGO_PKG_VERSION") }).to_string() }),
@@ -140,26 +199,23 @@ impl ApplicationServer {
                     .timeout(Duration::from_secs(*HTTP_TIMEOUT))
                     .layer(cors)
                     .layer(BufferLayer::new(4096))
-                    .layer(RateLimitLayer::new(
-                        rps.unwrap_or(*REQ_PER_SEC),
-                        Duration::from_secs(1),
+                    .layer(middleware::from_fn_with_state(
+                        rate_limiter,
+                        RateLimiter::middleware,
                     )),
             );
 
-        let router = router.fallback(Self::handle_404);
-
-        let port = config.port;
-        let addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, port));
-
-        tracing::info!("🚀 Server has launched on https://{addr}");
+        #[cfg(feature = "swagger-ui")]
+        let router = {
+            use utoipa::OpenApi;
 
-        axum::Server::bind(&addr)
-            .serve(router.into_make_service())
-            .with_graceful_shutdown(Self::shutdown_signal())
-            .await
-            .unwrap_or_else(|e| panic!("Server error: {}", e));
+            router.merge(utoipa_swagger_ui::SwaggerUi::new("/api/v1/docs").url(
+                "/api/v1/docs/openapi.json",
+                openapi_handler::ApiDoc::openapi(),
+            ))
+        };
 
-        Ok(())
+        router.fallback(Self::handle_404)
     }
 
     /// Adds a custom handler for tower's `TimeoutLayer`, see https://docs.rs/axum/latest/axum/middleware/index.html#commonly-used-middleware.
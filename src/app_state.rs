@@ -1,13 +1,25 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+
+use crate::entity::{access_list::AccessList, api_key::ApiKey, idempotency::IdempotencyRecord};
 
 #[derive(Clone, Default)]
 pub struct AppState {
     pub data: HashSet<String>,
+    pub access_list: AccessList,
+    pub api_keys: HashMap<String, ApiKey>,
+    pub api_key_usage: HashMap<String, (NaiveDate, u64)>,
+    pub idempotency_keys: HashMap<String, IdempotencyRecord>,
 }
 
 impl AppState {
-    pub fn add(&mut self, value: String) {
-        self.data.insert(value);
+    pub fn add(&mut self, value: String) -> bool {
+        self.data.insert(value)
+    }
+
+    pub fn remove(&mut self, value: &String) {
+        self.data.remove(value);
     }
 
     pub fn contains(&self, value: &String) -> bool {
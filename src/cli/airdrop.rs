@@ -0,0 +1,650 @@
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{bail, Context};
+use namada_sdk::{
+    address::Address,
+    args::{InputAmount, TxTransparentTransferData},
+    io::NullIo,
+    rpc, Namada,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config,
+    sdk::{
+        ledger::{LedgerClient, TxStatus},
+        namada::{self, NamadaClient},
+        transfer::{sign_transfer, submit},
+    },
+    utils::memo::render_airdrop_memo,
+};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct AirdropArgs {
+    /// CSV file with `address,token,amount` columns. Tokens are either an alias
+    /// from the token registry or an address, amounts are in base units.
+    #[clap(long)]
+    pub input: PathBuf,
+
+    /// Results file, rows already sent are skipped when resuming an airdrop.
+    /// Defaults to the input file with a `.results.csv` extension.
+    #[clap(long)]
+    pub output: Option<PathBuf>,
+
+    /// Number of transfers packed in a single transaction.
+    #[clap(long, default_value = "20")]
+    pub batch_size: usize,
+
+    /// Send again the rows whose transaction was broadcast by an earlier run
+    /// but can't be found on chain. Only use it once that transaction can no
+    /// longer be applied, otherwise the rows may be paid twice.
+    #[clap(long)]
+    pub resend_unconfirmed: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct AirdropRow {
+    address: String,
+    token: String,
+    amount: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum AirdropStatus {
+    /// Written before the transaction is broadcast.
+    Pending,
+    Sent,
+    Failed,
+}
+
+/// One line of the results file. Rows are appended as they change status, the
+/// last line of a row is its current status.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct AirdropResult {
+    row: usize,
+    #[serde(default)]
+    occurrence: usize,
+    address: String,
+    token: String,
+    amount: u64,
+    status: AirdropStatus,
+    tx_hash: Option<String>,
+}
+
+/// Results follow the contents of a row rather than its position, so rows can
+/// be added or moved between runs. Repeated rows are told apart by how many
+/// identical rows come before them.
+type AirdropKey = (String, String, u64, usize);
+
+impl AirdropResult {
+    fn key(&self) -> AirdropKey {
+        (
+            self.address.clone(),
+            self.token.clone(),
+            self.amount,
+            self.occurrence,
+        )
+    }
+}
+
+struct AirdropTransfer {
+    row: usize,
+    occurrence: usize,
+    entry: AirdropRow,
+    target: Address,
+    token: Address,
+}
+
+impl AirdropTransfer {
+    fn key(&self) -> AirdropKey {
+        (
+            self.entry.address.clone(),
+            self.entry.token.clone(),
+            self.entry.amount,
+            self.occurrence,
+        )
+    }
+
+    fn result(&self, status: AirdropStatus, tx_hash: Option<String>) -> AirdropResult {
+        AirdropResult {
+            row: self.row,
+            occurrence: self.occurrence,
+            address: self.entry.address.clone(),
+            token: self.entry.token.clone(),
+            amount: self.entry.amount,
+            status,
+            tx_hash,
+        }
+    }
+}
+
+/// Where the rows of an airdrop stand before sending anything.
+#[derive(Default)]
+struct AirdropPlan {
+    /// Rows to send in this run.
+    pending: Vec<AirdropTransfer>,
+    /// Rows found applied on chain since the last run.
+    confirmed: Vec<AirdropResult>,
+    done: usize,
+    unconfirmed: usize,
+}
+
+pub async fn run(config: &Config, args: AirdropArgs) -> anyhow::Result<()> {
+    if args.batch_size == 0 {
+        bail!("batch size must be positive");
+    }
+
+    let output = args
+        .output
+        .clone()
+        .unwrap_or_else(|| args.input.with_extension("results.csv"));
+
+    let (sdk, accounts) = namada::init(config).await?;
+    let sdk = Arc::new(sdk);
+    let ledger = NamadaClient::new(sdk.clone(), None);
+    let native_token = rpc::query_native_token(&sdk.clone_client()).await?;
+
+    let transfers = read_transfers(&args.input, &config.tokens, &native_token)?;
+    let previous = previous_results(&output)?;
+    let plan = resume(&ledger, transfers, &previous, args.resend_unconfirmed).await?;
+
+    tracing::info!(
+        "Airdrop of {} transfers, {} already sent",
+        plan.pending.len() + plan.done + plan.unconfirmed,
+        plan.done
+    );
+
+    // Dry runs build and sign every batch but leave the results file alone.
+    let mut writer = if config.dry_run {
+        None
+    } else {
+        let mut writer = results_writer(&output)?;
+        for result in &plan.confirmed {
+            writer.serialize(result)?;
+        }
+        writer.flush()?;
+        Some(writer)
+    };
+    let AirdropPlan {
+        pending,
+        unconfirmed,
+        ..
+    } = plan;
+
+    let memo = render_airdrop_memo(&config.airdrop_memo, &config.chain_id, &config.campaign);
+    let source = accounts.primary();
+    let client = sdk.clone_client();
+    let mut failed = 0;
+
+    for batch in pending.chunks(args.batch_size) {
+        let mut data = Vec::new();
+        for transfer in batch {
+            let denominated_amount = rpc::denominate_amount(
+                &client,
+                &NullIo,
+                &transfer.token,
+                transfer.entry.amount.into(),
+            )
+            .await;
+            data.push(TxTransparentTransferData {
+                source: source.address.clone(),
+                target: transfer.target.clone(),
+                token: transfer.token.clone(),
+                amount: InputAmount::Unvalidated(denominated_amount),
+            });
+        }
+
+        let (tx, tx_args) =
+            match sign_transfer(&sdk, &source.public_key, data, &memo, &config.fees).await {
+                Ok(signed) => signed,
+                Err(e) => {
+                    tracing::error!("Unable to build the airdrop transaction: {}", e);
+                    record(&mut writer, batch, AirdropStatus::Failed, None)?;
+                    failed += batch.len();
+                    continue;
+                }
+            };
+
+        let tx_hash = tx.header_hash().to_string();
+        if config.dry_run {
+            tracing::info!(
+                "Dry run, batch of {} transfers not broadcast: {}",
+                batch.len(),
+                tx_hash
+            );
+            continue;
+        }
+
+        // Record the hash first so a crash during the broadcast can't lead to
+        // the batch being sent twice.
+        record(
+            &mut writer,
+            batch,
+            AirdropStatus::Pending,
+            Some(tx_hash.clone()),
+        )?;
+
+        let sent = match submit(&sdk, tx, &tx_args).await {
+            Ok(outcome) => outcome.applied,
+            Err(e) => {
+                tracing::error!("Unable to send transfer: {}", e);
+                false
+            }
+        };
+        let status = if sent {
+            AirdropStatus::Sent
+        } else {
+            AirdropStatus::Failed
+        };
+        record(&mut writer, batch, status, Some(tx_hash.clone()))?;
+
+        if !sent {
+            failed += batch.len();
+        }
+        tracing::info!(
+            "Batch of {} transfers {}: {}",
+            batch.len(),
+            if sent { "sent" } else { "failed" },
+            tx_hash
+        );
+    }
+
+    if failed > 0 || unconfirmed > 0 {
+        bail!(
+            "{} transfers failed and {} are not confirmed yet, run the airdrop again to retry them",
+            failed,
+            unconfirmed
+        );
+    }
+
+    if config.dry_run {
+        tracing::info!("Airdrop dry run done, nothing was broadcast");
+    } else {
+        tracing::info!("Airdrop done, results written to {}", output.display());
+    }
+
+    Ok(())
+}
+
+/// Reads and validates every row of `input`, reporting all invalid rows at
+/// once.
+fn read_transfers(
+    input: &Path,
+    tokens: &HashMap<String, Address>,
+    native_token: &Address,
+) -> anyhow::Result<Vec<AirdropTransfer>> {
+    let mut reader =
+        csv::Reader::from_path(input).with_context(|| format!("can't read {}", input.display()))?;
+
+    let mut errors = Vec::new();
+    let mut transfers = Vec::new();
+    let mut occurrences = HashMap::new();
+    for (index, entry) in reader.deserialize::<AirdropRow>().enumerate() {
+        let row = index + 1;
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                errors.push(format!("row {}: {}", row, e));
+                continue;
+            }
+        };
+        let Ok(target) = Address::decode(&entry.address) else {
+            errors.push(format!("row {}: invalid address {}", row, entry.address));
+            continue;
+        };
+        let Some(token) = resolve_token(tokens, native_token, &entry.token) else {
+            errors.push(format!("row {}: unknown token {}", row, entry.token));
+            continue;
+        };
+        if entry.amount == 0 {
+            errors.push(format!("row {}: amount must be positive", row));
+            continue;
+        }
+
+        let occurrence = occurrences
+            .entry((entry.address.clone(), entry.token.clone(), entry.amount))
+            .or_insert(0);
+        transfers.push(AirdropTransfer {
+            row,
+            occurrence: *occurrence,
+            entry,
+            target,
+            token,
+        });
+        *occurrence += 1;
+    }
+
+    if !errors.is_empty() {
+        bail!("invalid airdrop file:\n{}", errors.join("\n"));
+    }
+
+    Ok(transfers)
+}
+
+/// Rows whose transaction was broadcast before are only sent again once the
+/// chain says that transaction failed.
+async fn resume(
+    ledger: &dyn LedgerClient,
+    transfers: Vec<AirdropTransfer>,
+    previous: &HashMap<AirdropKey, AirdropResult>,
+    resend_unconfirmed: bool,
+) -> anyhow::Result<AirdropPlan> {
+    let mut plan = AirdropPlan::default();
+    for transfer in transfers {
+        let Some(result) = previous.get(&transfer.key()) else {
+            plan.pending.push(transfer);
+            continue;
+        };
+        let tx_hash = match (result.status, &result.tx_hash) {
+            (AirdropStatus::Sent, _) => {
+                plan.done += 1;
+                continue;
+            }
+            (_, Some(tx_hash)) => tx_hash,
+            (_, None) => {
+                plan.pending.push(transfer);
+                continue;
+            }
+        };
+
+        match ledger.tx_status(tx_hash).await? {
+            TxStatus::Applied => {
+                plan.confirmed
+                    .push(transfer.result(AirdropStatus::Sent, Some(tx_hash.clone())));
+                plan.done += 1;
+            }
+            TxStatus::Failed => plan.pending.push(transfer),
+            TxStatus::Pending if resend_unconfirmed => plan.pending.push(transfer),
+            TxStatus::Pending => {
+                tracing::warn!(
+                    "Row {} was broadcast in {} which isn't on chain yet, not sending it again",
+                    transfer.row,
+                    tx_hash
+                );
+                plan.unconfirmed += 1;
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+/// Appends the status of `batch`, unless this is a dry run.
+fn record(
+    writer: &mut Option<csv::Writer<File>>,
+    batch: &[AirdropTransfer],
+    status: AirdropStatus,
+    tx_hash: Option<String>,
+) -> anyhow::Result<()> {
+    let Some(writer) = writer else {
+        return Ok(());
+    };
+    for transfer in batch {
+        writer.serialize(transfer.result(status, tx_hash.clone()))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Tokens must be in the registry when one is configured, otherwise any
+/// address or the `NAM` alias for the native token is accepted.
+fn resolve_token(
+    tokens: &HashMap<String, Address>,
+    native_token: &Address,
+    token: &str,
+) -> Option<Address> {
+    if let Some(address) = tokens.get(token) {
+        return Some(address.clone());
+    }
+    if tokens.is_empty() && token == "NAM" {
+        return Some(native_token.clone());
+    }
+
+    let address = Address::decode(token).ok()?;
+    if tokens.is_empty() || tokens.values().any(|known| known == &address) {
+        Some(address)
+    } else {
+        None
+    }
+}
+
+/// Current status of every row in the results file.
+fn previous_results(path: &Path) -> anyhow::Result<HashMap<AirdropKey, AirdropResult>> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let mut reader =
+        csv::Reader::from_path(path).with_context(|| format!("can't read {}", path.display()))?;
+    let mut results = HashMap::new();
+    for result in reader.deserialize::<AirdropResult>() {
+        let result = result?;
+        results.insert(result.key(), result);
+    }
+
+    Ok(results)
+}
+
+fn results_writer(path: &Path) -> anyhow::Result<csv::Writer<File>> {
+    let write_headers = !path.exists();
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("can't open {}", path.display()))?;
+
+    Ok(csv::WriterBuilder::new()
+        .has_headers(write_headers)
+        .from_writer(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use namada_sdk::{
+        key::{common, ed25519, RefTo, SigScheme},
+        token::Amount,
+    };
+    use rand::rngs::OsRng;
+
+    use super::*;
+    use crate::{
+        config::FeeSettings,
+        sdk::{ledger::Transfer, mock::MockLedger},
+    };
+
+    fn secret_key() -> common::SecretKey {
+        common::SecretKey::Ed25519(ed25519::SigScheme::generate(&mut OsRng))
+    }
+
+    fn address() -> Address {
+        Address::from(&secret_key().ref_to())
+    }
+
+    fn temp_file(name: &str, content: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("namada-faucet-airdrop-{}", nanos));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn csv(rows: &[(&Address, u64)]) -> String {
+        rows.iter().fold(
+            "address,token,amount\n".to_string(),
+            |csv, (address, amount)| format!("{}{},NAM,{}\n", csv, address, amount),
+        )
+    }
+
+    /// Broadcasts a transfer through `ledger`, returning its hash.
+    async fn broadcast_tx(ledger: &MockLedger, token: &Address) -> String {
+        let signer = secret_key();
+        let source = Address::from(&signer.ref_to());
+        ledger.set_balance(token, &source, Amount::from(1_000_000));
+        let transfer = Transfer {
+            source,
+            target: address(),
+            token: token.clone(),
+            amount: Amount::from(1),
+        };
+
+        ledger
+            .transfer(
+                &signer.ref_to(),
+                transfer,
+                "",
+                &FeeSettings::default(),
+                false,
+            )
+            .await
+            .unwrap()
+            .hash
+    }
+
+    #[test]
+    fn every_invalid_row_is_reported() {
+        let native_token = address();
+        let input = temp_file(
+            "airdrop.csv",
+            &format!(
+                "address,token,amount\n\
+                 not-an-address,NAM,1\n\
+                 {0},BTC,1\n\
+                 {0},NAM,0\n\
+                 {0},NAM,lots\n\
+                 {0},NAM,1\n",
+                address()
+            ),
+        );
+
+        let error = read_transfers(&input, &HashMap::new(), &native_token)
+            .err()
+            .unwrap()
+            .to_string();
+
+        for expected in [
+            "row 1: invalid address",
+            "row 2: unknown token BTC",
+            "row 3: amount must be positive",
+            "row 4: ",
+        ] {
+            assert!(
+                error.contains(expected),
+                "missing {:?} in {}",
+                expected,
+                error
+            );
+        }
+        assert!(!error.contains("row 5"));
+    }
+
+    #[test]
+    fn repeated_rows_are_counted_apart() {
+        let native_token = address();
+        let (first, second) = (address(), address());
+        let input = temp_file(
+            "airdrop.csv",
+            &csv(&[(&first, 1), (&second, 1), (&first, 1)]),
+        );
+
+        let transfers = read_transfers(&input, &HashMap::new(), &native_token).unwrap();
+
+        let occurrences: Vec<_> = transfers
+            .iter()
+            .map(|transfer| transfer.occurrence)
+            .collect();
+        assert_eq!(occurrences, [0, 0, 1]);
+    }
+
+    #[tokio::test]
+    async fn resuming_follows_rows_moved_in_an_edited_file() {
+        let native_token = address();
+        let ledger = MockLedger::new(native_token.clone(), Amount::from(1));
+        let (sent, broadcast, failed, added) = (address(), address(), address(), address());
+
+        let input = temp_file(
+            "airdrop.csv",
+            &csv(&[(&sent, 1), (&broadcast, 2), (&failed, 3), (&broadcast, 2)]),
+        );
+        let transfers = read_transfers(&input, &HashMap::new(), &native_token).unwrap();
+        let applied = broadcast_tx(&ledger, &native_token).await;
+        ledger.hold(true);
+        let rejected = broadcast_tx(&ledger, &native_token).await;
+        ledger.settle(&rejected, TxStatus::Failed).unwrap();
+        let results = [
+            transfers[0].result(AirdropStatus::Sent, Some(applied.clone())),
+            transfers[1].result(AirdropStatus::Pending, Some(applied)),
+            transfers[2].result(AirdropStatus::Pending, Some(rejected)),
+        ];
+        let output = input.with_extension("results.csv");
+        let mut writer = results_writer(&output).unwrap();
+        for result in &results {
+            writer.serialize(result).unwrap();
+        }
+        writer.flush().unwrap();
+
+        // A row is added at the top and the file is reordered, the results
+        // still follow the rows they were written for.
+        fs::write(
+            &input,
+            csv(&[
+                (&added, 4),
+                (&broadcast, 2),
+                (&failed, 3),
+                (&broadcast, 2),
+                (&sent, 1),
+            ]),
+        )
+        .unwrap();
+        let transfers = read_transfers(&input, &HashMap::new(), &native_token).unwrap();
+        let previous = previous_results(&output).unwrap();
+        let plan = resume(&ledger, transfers, &previous, false).await.unwrap();
+
+        let pending: Vec<_> = plan
+            .pending
+            .iter()
+            .map(|transfer| (transfer.row, transfer.occurrence))
+            .collect();
+        assert_eq!(pending, [(1, 0), (3, 0), (4, 1)]);
+        assert_eq!(plan.done, 2);
+        assert_eq!(plan.unconfirmed, 0);
+        assert_eq!(plan.confirmed.len(), 1);
+        assert_eq!(plan.confirmed[0].row, 2);
+        assert_eq!(plan.confirmed[0].status, AirdropStatus::Sent);
+    }
+
+    #[tokio::test]
+    async fn unconfirmed_rows_are_only_sent_again_when_asked() {
+        let native_token = address();
+        let ledger = MockLedger::new(native_token.clone(), Amount::from(1));
+        ledger.hold(true);
+        let in_mempool = broadcast_tx(&ledger, &native_token).await;
+
+        let input = temp_file("airdrop.csv", &csv(&[(&address(), 1)]));
+        let transfers = read_transfers(&input, &HashMap::new(), &native_token).unwrap();
+        let result = transfers[0].result(AirdropStatus::Pending, Some(in_mempool));
+        let previous = HashMap::from([(result.key(), result)]);
+
+        let plan = resume(&ledger, transfers, &previous, false).await.unwrap();
+        assert!(plan.pending.is_empty());
+        assert_eq!(plan.unconfirmed, 1);
+
+        let transfers = read_transfers(&input, &HashMap::new(), &native_token).unwrap();
+        let plan = resume(&ledger, transfers, &previous, true).await.unwrap();
+        assert_eq!(plan.pending.len(), 1);
+    }
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use namada_sdk::rpc;
+
+use crate::{
+    config::Config,
+    sdk::{ledger::LedgerClient, namada, namada::NamadaClient},
+};
+
+/// Prints the balance of every faucet account for each configured token, or
+/// the native token when no token registry is configured.
+pub async fn run(config: &Config) -> anyhow::Result<()> {
+    let (sdk, accounts) = namada::init(config).await?;
+
+    let mut tokens = config.tokens.clone().into_iter().collect::<Vec<_>>();
+    if tokens.is_empty() {
+        let native_token = rpc::query_native_token(&sdk.clone_client()).await?;
+        tokens.push(("NAM".to_string(), native_token));
+    }
+    tokens.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let ledger = NamadaClient::new(Arc::new(sdk), None);
+    for account in accounts.accounts() {
+        println!("{} ({})", account.alias, account.address);
+        for (alias, token) in &tokens {
+            let balance = ledger.balance(token, &account.address).await?;
+            println!("  {}: {}", alias, ledger.denominate(token, balance).await);
+        }
+    }
+
+    Ok(())
+}
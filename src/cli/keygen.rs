@@ -0,0 +1,15 @@
+use rand::{distributions::Alphanumeric, rngs::OsRng, Rng};
+
+/// Length of the `auth_key` setting.
+const AUTH_KEY_LENGTH: usize = 32;
+
+/// Prints a random `auth_key`, 32 alphanumeric characters from the OS RNG.
+pub fn gen_auth_key() {
+    let key: String = OsRng
+        .sample_iter(&Alphanumeric)
+        .take(AUTH_KEY_LENGTH)
+        .map(char::from)
+        .collect();
+
+    println!("{}", key);
+}
@@ -0,0 +1,59 @@
+use std::{fs::OpenOptions, io::Write, path::PathBuf};
+
+use anyhow::Context;
+
+use crate::sdk::key::{read_secret, KeySource, Keystore};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct KeystoreArgs {
+    #[clap(subcommand)]
+    pub command: KeystoreCommand,
+}
+
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum KeystoreCommand {
+    /// Encrypt a secret key into a keystore usable with the `keystore` setting.
+    Create(CreateArgs),
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct CreateArgs {
+    /// File with the secret key to encrypt.
+    #[clap(long)]
+    pub key_file: PathBuf,
+
+    /// File with the keystore password, pass the same file as
+    /// `keystore_password_file` to the faucet.
+    #[clap(long)]
+    pub password_file: PathBuf,
+
+    /// Keystore to write, an existing file is never overwritten.
+    #[clap(long)]
+    pub output: PathBuf,
+}
+
+pub fn run(args: KeystoreArgs) -> anyhow::Result<()> {
+    match args.command {
+        KeystoreCommand::Create(args) => create(args),
+    }
+}
+
+fn create(args: CreateArgs) -> anyhow::Result<()> {
+    let sk = KeySource::File(args.key_file).load()?;
+    let password = read_secret(&args.password_file)?;
+    let keystore = Keystore::encrypt(&sk.to_string(), &password)?;
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(&args.output)
+        .with_context(|| format!("can't create keystore {}", args.output.display()))?;
+    serde_json::to_writer_pretty(&mut file, &keystore)?;
+    file.write_all(b"\n")?;
+
+    println!("Keystore written to {}", args.output.display());
+
+    Ok(())
+}
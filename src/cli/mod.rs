@@ -0,0 +1,39 @@
+pub mod airdrop;
+pub mod balance;
+pub mod keygen;
+pub mod keystore;
+#[cfg(feature = "client")]
+pub mod request;
+#[cfg(feature = "client")]
+pub mod solve;
+
+use crate::config::AppConfig;
+
+#[derive(clap::Parser)]
+pub struct Cli {
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+
+    #[clap(flatten)]
+    pub config: AppConfig,
+}
+
+#[derive(clap::Subcommand)]
+pub enum Command {
+    /// Run the faucet server, the default when no command is given.
+    Serve,
+    /// Fund the accounts listed in a CSV file.
+    Airdrop(airdrop::AirdropArgs),
+    /// Request tokens from a running faucet.
+    #[cfg(feature = "client")]
+    Request(request::RequestArgs),
+    /// Solve a proof of work challenge offline.
+    #[cfg(feature = "client")]
+    Solve(solve::SolveArgs),
+    /// Generate a random `auth_key`.
+    GenAuthKey,
+    /// Manage encrypted keystores.
+    Keystore(keystore::KeystoreArgs),
+    /// Show the balances of the faucet accounts for each configured token.
+    Balance,
+}
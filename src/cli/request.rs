@@ -0,0 +1,55 @@
+use anyhow::Context;
+use reqwest::Url;
+
+use crate::client::FaucetClient;
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct RequestArgs {
+    /// Faucet to request from.
+    #[clap(long, default_value = "http://127.0.0.1:5000")]
+    pub url: Url,
+
+    /// Token alias from the faucet's registry or token address.
+    #[clap(long, default_value = "NAM")]
+    pub token: String,
+
+    /// Address receiving the tokens.
+    #[clap(long)]
+    pub target: String,
+
+    /// Amount in base units.
+    #[clap(long)]
+    pub amount: u64,
+
+    /// Authenticates with an API key instead of solving the challenge.
+    #[clap(long, env = "FAUCET_API_KEY")]
+    pub api_key: Option<String>,
+
+    /// Solver threads, one per core by default.
+    #[clap(long)]
+    pub threads: Option<usize>,
+}
+
+pub async fn run(args: RequestArgs) -> anyhow::Result<()> {
+    let mut client = FaucetClient::new(args.url);
+    if let Some(threads) = args.threads {
+        client = client.with_threads(threads);
+    }
+    if let Some(api_key) = args.api_key {
+        client = client.with_api_key(api_key);
+    }
+
+    let settings = client.settings().await.context("can't reach the faucet")?;
+    let token = settings
+        .tokens_alias_to_address
+        .get(&args.token)
+        .cloned()
+        .unwrap_or(args.token);
+
+    let status = client
+        .request_transfer(token, args.target, args.amount)
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&status)?);
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use anyhow::bail;
+
+use crate::client::solver;
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct SolveArgs {
+    /// Hex encoded challenge returned by `GET /api/v1/faucet`.
+    #[clap(long)]
+    pub challenge: String,
+
+    /// Number of leading zero hex digits, see `/api/v1/faucet/setting`.
+    #[clap(long)]
+    pub difficulty: u64,
+
+    /// Solver threads, one per core by default.
+    #[clap(long)]
+    pub threads: Option<usize>,
+}
+
+pub fn run(args: SolveArgs) -> anyhow::Result<()> {
+    let threads = args.threads.unwrap_or_else(solver::default_threads);
+    match solver::solve(&args.challenge, args.difficulty, threads) {
+        Some(solution) => println!("{}", solution),
+        None => bail!("challenge must be lowercase hex"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ClientError {
+    #[error("Request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Faucet returned {status}: {message}")]
+    Api {
+        status: u16,
+        message: String,
+        /// From the `Retry-After` header, when the faucet asks to come back.
+        retry_after: Option<Duration>,
+    },
+    #[error("Invalid challenge {0}")]
+    InvalidChallenge(String),
+}
+
+impl ClientError {
+    /// Whether the same request can be sent again. Rate limited and
+    /// overloaded responses are rejected before the faucet acts on them.
+    /// Timed out requests may have been acted on, they are only safe to
+    /// repeat because every request the client sends is either a read or
+    /// carries an idempotency key.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ClientError::Http(error) => error.is_connect() || error.is_timeout(),
+            ClientError::Api { status, .. } => matches!(status, 408 | 429 | 502 | 503 | 504),
+            ClientError::InvalidChallenge(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_error(status: u16) -> ClientError {
+        ClientError::Api {
+            status,
+            message: String::new(),
+            retry_after: None,
+        }
+    }
+
+    #[test]
+    fn timeouts_and_overloads_are_transient() {
+        for status in [408, 429, 502, 503, 504] {
+            assert!(api_error(status).is_transient(), "{}", status);
+        }
+        for status in [400, 401, 403, 409, 500] {
+            assert!(!api_error(status).is_transient(), "{}", status);
+        }
+    }
+}
@@ -0,0 +1,244 @@
+pub mod error;
+pub mod solver;
+
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use data_encoding::HEXLOWER;
+use reqwest::{header::RETRY_AFTER, Response, Url};
+use serde::de::DeserializeOwned;
+
+use crate::dto::faucet::{
+    FaucetRequestDto, FaucetResponseDto, FaucetResponseStatusDto, FaucetSettingResponse, Transfer,
+};
+
+use self::error::ClientError;
+
+/// How long to keep waiting for a transfer the faucet is still processing.
+const IN_FLIGHT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Per request, a little over the faucet's own 30 second timeout so that
+/// its `408` normally arrives first.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(40);
+
+/// How failed requests are retried. The delay doubles after each attempt.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Client for the faucet HTTP API. `url` is the faucet root, e.g.
+/// `http://127.0.0.1:5000`.
+#[derive(Clone, Debug)]
+pub struct FaucetClient {
+    http: reqwest::Client,
+    url: Url,
+    retry: RetryPolicy,
+    threads: usize,
+    api_key: Option<String>,
+}
+
+impl FaucetClient {
+    pub fn new(url: Url) -> Self {
+        Self {
+            http: Self::http(REQUEST_TIMEOUT),
+            url,
+            retry: RetryPolicy::default(),
+            threads: solver::default_threads(),
+            api_key: None,
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.http = Self::http(timeout);
+        self
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Skips the proof of work, the faucet checks the key instead.
+    pub fn with_api_key(mut self, api_key: String) -> Self {
+        self.api_key = Some(api_key);
+        self
+    }
+
+    pub async fn settings(&self) -> Result<FaucetSettingResponse, ClientError> {
+        self.retry(|| self.get("api/v1/faucet/setting")).await
+    }
+
+    pub async fn challenge(&self) -> Result<FaucetResponseDto, ClientError> {
+        self.retry(|| self.get("api/v1/faucet")).await
+    }
+
+    /// Sends a transfer request as is. Retries carry the same idempotency
+    /// key, so the faucet sends the transfer at most once, even when an
+    /// attempt timed out while the faucet was still working on it.
+    pub async fn submit(
+        &self,
+        request: &FaucetRequestDto,
+    ) -> Result<FaucetResponseStatusDto, ClientError> {
+        let idempotency_key = HEXLOWER.encode(&rand::random::<[u8; 16]>());
+        self.retry(|| async {
+            let mut builder = self
+                .http
+                .post(self.endpoint("api/v1/faucet"))
+                .header("idempotency-key", &idempotency_key)
+                .json(request);
+            if let Some(api_key) = &self.api_key {
+                builder = builder.header("x-api-key", api_key);
+            }
+            Self::parse(builder.send().await?).await
+        })
+        .await
+    }
+
+    /// Fetches a challenge, solves it and requests `amount` (base units) of
+    /// `token` for `target`.
+    pub async fn request_transfer(
+        &self,
+        token: String,
+        target: String,
+        amount: u64,
+    ) -> Result<FaucetResponseStatusDto, ClientError> {
+        let transfer = Transfer {
+            token,
+            target,
+            amount,
+        };
+
+        let request = if self.api_key.is_some() {
+            FaucetRequestDto {
+                solution: None,
+                challenge: None,
+                tag: None,
+                transfer,
+                memo: None,
+                public_key: None,
+                signature: None,
+            }
+        } else {
+            let settings = self.settings().await?;
+            let challenge = self.challenge().await?;
+            let solution = self
+                .solve(&challenge.challenge, settings.difficulty)
+                .await?;
+
+            FaucetRequestDto {
+                solution: Some(solution),
+                challenge: Some(challenge.challenge),
+                tag: Some(challenge.tag),
+                transfer,
+                memo: None,
+                public_key: None,
+                signature: None,
+            }
+        };
+
+        self.submit(&request).await
+    }
+
+    /// Solves `challenge` off the async runtime.
+    pub async fn solve(&self, challenge: &str, difficulty: u64) -> Result<String, ClientError> {
+        let owned = challenge.to_string();
+        let threads = self.threads;
+        tokio::task::spawn_blocking(move || solver::solve(&owned, difficulty, threads))
+            .await
+            .ok()
+            .flatten()
+            .ok_or_else(|| ClientError::InvalidChallenge(challenge.to_string()))
+    }
+
+    fn http(timeout: Duration) -> reqwest::Client {
+        reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("the HTTP client settings are valid")
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
+        Self::parse(self.http.get(self.endpoint(path)).send().await?).await
+    }
+
+    fn endpoint(&self, path: &str) -> Url {
+        self.url.join(path).unwrap_or_else(|_| self.url.clone())
+    }
+
+    async fn parse<T: DeserializeOwned>(response: Response) -> Result<T, ClientError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response.json().await?);
+        }
+
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_secs);
+
+        let message = response
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|body| body["message"].as_str().map(str::to_string))
+            .unwrap_or_else(|| status.to_string());
+
+        Err(ClientError::Api {
+            status: status.as_u16(),
+            message,
+            retry_after,
+        })
+    }
+
+    async fn retry<T, F, Fut>(&self, request: F) -> Result<T, ClientError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let mut backoff = self.retry.backoff;
+        let mut attempt = 1;
+        let started = Instant::now();
+        loop {
+            match request().await {
+                // An earlier attempt timed out, on our side or the faucet's,
+                // but is still being processed. Waiting for it doesn't use up
+                // an attempt.
+                Err(ClientError::Api {
+                    status: 409,
+                    retry_after: Some(delay),
+                    ..
+                }) if started.elapsed() < IN_FLIGHT_TIMEOUT => {
+                    tracing::debug!("Waiting for the faucet to finish the request");
+                    tokio::time::sleep(delay).await;
+                }
+                Err(error) if error.is_transient() && attempt < self.retry.attempts => {
+                    tracing::debug!("Retrying faucet request after {}", error);
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
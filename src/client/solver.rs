@@ -0,0 +1,59 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use data_encoding::HEXLOWER;
+
+/// Nonces searched between checks for a solution found by another thread.
+const BATCH: u64 = 1 << 16;
+
+/// Brute forces a solution to `challenge` on `threads` threads. Solutions
+/// are 8 byte big endian counters, hex encoded. Returns `None` if the
+/// challenge isn't lowercase hex.
+pub fn solve(challenge: &str, difficulty: u64, threads: usize) -> Option<String> {
+    let challenge = HEXLOWER.decode(challenge.as_bytes()).ok()?;
+
+    let threads = threads.max(1) as u64;
+    let found = Arc::new(AtomicBool::new(false));
+
+    let workers = (0..threads)
+        .map(|offset| {
+            let challenge = challenge.clone();
+            let found = found.clone();
+            thread::spawn(move || {
+                // Search in batches so the thread notices another one found a
+                // solution.
+                let mut batch = 0_u64;
+                while !found.load(Ordering::Relaxed) {
+                    let start = batch.checked_mul(BATCH)?;
+                    let nonces = (start..start.checked_add(BATCH)?)
+                        .skip(offset as usize)
+                        .step_by(threads as usize);
+                    if let Some(solution) = namada_faucet_pow::solve(&challenge, difficulty, nonces)
+                    {
+                        found.store(true, Ordering::Relaxed);
+                        return Some(HEXLOWER.encode(&solution));
+                    }
+                    batch += 1;
+                }
+                None
+            })
+        })
+        .collect::<Vec<_>>();
+
+    workers
+        .into_iter()
+        .filter_map(|worker| worker.join().ok().flatten())
+        .next()
+}
+
+/// Number of threads to solve with by default, one per core.
+pub fn default_threads() -> usize {
+    thread::available_parallelism()
+        .map(|threads| threads.get())
+        .unwrap_or(1)
+}
@@ -1,35 +1,806 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
+
+use namada_sdk::{address::Address, chain::ChainId, key::common::SecretKey};
+use serde::Deserialize;
+use tendermint::{block::Height, hash::Algorithm, Hash};
+use tendermint_rpc::Url;
+use zeroize::Zeroizing;
+
+use crate::{
+    entity::{
+        access_list::AccessList,
+        address::{AddressKind, DEFAULT_ALLOWED_TARGETS},
+        api_key::ApiKey,
+    },
+    error::config::ConfigError,
+    sdk::key::KeySource,
+    state::pool::DispatchStrategy,
+    utils::memo::{DEFAULT_AIRDROP_MEMO, DEFAULT_MEMO_TEMPLATE},
+};
+
+const DEFAULT_PORT: u16 = 5000;
+const DEFAULT_WITHDRAW_LIMIT: u64 = 1000;
+const DEFAULT_REFILL_COOLDOWN: u64 = 600;
+const DEFAULT_REFILL_INTERVAL: u64 = 60;
+const DEFAULT_TRUSTING_PERIOD: u64 = 14 * 24 * 60 * 60;
+const AUTH_KEY_LENGTH: usize = 32;
+/// A SHA-256 digest has 64 hex digits, anything above can't be solved.
+const MAX_DIFFICULTY: u64 = 64;
+
 #[derive(clap::ValueEnum, Clone, Debug, Copy)]
 pub enum CargoEnv {
     Development,
     Production,
 }
 
-#[derive(clap::Parser)]
+/// Command line and environment arguments. Every value set here overrides the
+/// one coming from the configuration file. The flags are global so they can
+/// be given before or after the subcommand.
+#[derive(clap::Parser, Clone)]
 pub struct AppConfig {
-    #[clap(long, env, default_value = "5000")]
-    pub port: u16,
+    #[clap(long, env, global = true)]
+    pub config: Option<PathBuf>,
 
-    #[clap(long, env)]
-    pub difficulty: u64,
+    #[clap(long, env, global = true)]
+    pub port: Option<u16>,
 
-    #[clap(long, env)]
-    pub private_key: String,
+    #[clap(long, env, global = true)]
+    pub difficulty: Option<u64>,
 
-    #[clap(long, env)]
-    pub chain_start: i64,
+    /// Kept for reloads, wiped from memory when dropped.
+    #[clap(long, env, global = true)]
+    pub private_key: Option<Zeroizing<String>>,
+
+    #[clap(long, env, global = true)]
+    pub private_key_file: Option<PathBuf>,
+
+    #[clap(long, env, global = true)]
+    pub wallet_dir: Option<PathBuf>,
+
+    #[clap(long, env, global = true)]
+    pub wallet_alias: Option<String>,
+
+    #[clap(long, env, global = true)]
+    pub wallet_password_file: Option<PathBuf>,
+
+    #[clap(long, env, global = true)]
+    pub keystore: Option<PathBuf>,
+
+    #[clap(long, env, global = true)]
+    pub keystore_password_file: Option<PathBuf>,
+
+    #[clap(long, env, global = true, value_delimiter = ',')]
+    pub pool_key_files: Vec<PathBuf>,
+
+    #[clap(long, env, global = true, value_enum)]
+    pub dispatch: Option<DispatchStrategy>,
 
-    #[clap(long, env)]
-    pub chain_id: String,
+    #[clap(long, env, global = true)]
+    pub treasury_key_file: Option<PathBuf>,
 
-    #[clap(long, env)]
-    pub rpc: String,
+    #[clap(long, env, global = true)]
+    pub refill_token: Option<String>,
 
-    #[clap(long, env)]
+    #[clap(long, env, global = true)]
+    pub refill_threshold: Option<u64>,
+
+    #[clap(long, env, global = true)]
+    pub refill_target: Option<u64>,
+
+    #[clap(long, env, global = true)]
+    pub refill_cooldown: Option<u64>,
+
+    #[clap(long, env, global = true)]
+    pub refill_max_per_day: Option<u64>,
+
+    #[clap(long, env, global = true)]
+    pub refill_interval: Option<u64>,
+
+    #[clap(long, env, global = true)]
+    pub chain_start: Option<i64>,
+
+    #[clap(long, env, global = true)]
+    pub chain_id: Option<String>,
+
+    #[clap(long, env, global = true)]
+    pub rpc: Option<String>,
+
+    #[clap(long, env, global = true)]
     pub withdraw_limit: Option<u64>,
 
-    #[clap(long, env)]
+    #[clap(long, env, global = true)]
     pub auth_key: Option<String>,
 
-    #[clap(long, env)]
+    #[clap(long, env, global = true)]
     pub rps: Option<u64>,
+
+    #[clap(long, env, global = true)]
+    pub access_list: Option<PathBuf>,
+
+    #[clap(long, env, global = true)]
+    pub admin_key: Option<String>,
+
+    #[clap(long, env, global = true)]
+    pub api_keys: Option<PathBuf>,
+
+    #[clap(long, env, global = true)]
+    pub dry_run: bool,
+
+    #[clap(long, env, global = true)]
+    pub fee_amount: Option<u64>,
+
+    #[clap(long, env, global = true)]
+    pub gas_limit: Option<u64>,
+
+    #[clap(long, env, global = true)]
+    pub fee_token: Option<String>,
+
+    #[clap(long, env, global = true)]
+    pub memo_template: Option<String>,
+
+    #[clap(long, env, global = true)]
+    pub campaign: Option<String>,
+
+    #[clap(long, env, global = true)]
+    pub airdrop_memo: Option<String>,
+
+    #[clap(long, env, global = true, value_enum, value_delimiter = ',')]
+    pub allowed_targets: Vec<AddressKind>,
+
+    #[clap(long, env, global = true)]
+    pub target_balance_threshold: Option<u64>,
+
+    #[clap(long, env, global = true, value_enum)]
+    pub target_balance_policy: Option<TargetBalancePolicy>,
+
+    #[clap(long, env, global = true)]
+    pub require_ownership_proof: bool,
+
+    #[clap(long, env, global = true)]
+    pub reveal_pk: bool,
+
+    #[clap(long, env, global = true)]
+    pub trust_height: Option<u64>,
+
+    #[clap(long, env, global = true)]
+    pub trust_hash: Option<String>,
+
+    #[clap(long, env, global = true)]
+    pub trusting_period: Option<u64>,
+}
+
+/// Configuration file, either TOML or YAML depending on the file extension.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileConfig {
+    pub port: Option<u16>,
+    pub difficulty: Option<u64>,
+    pub private_key: Option<Zeroizing<String>>,
+    pub private_key_file: Option<PathBuf>,
+    pub wallet_dir: Option<PathBuf>,
+    pub wallet_alias: Option<String>,
+    pub wallet_password_file: Option<PathBuf>,
+    pub keystore: Option<PathBuf>,
+    pub keystore_password_file: Option<PathBuf>,
+    #[serde(default)]
+    pub pool_key_files: Vec<PathBuf>,
+    pub dispatch: Option<DispatchStrategy>,
+    pub treasury_key_file: Option<PathBuf>,
+    pub refill_token: Option<String>,
+    pub refill_threshold: Option<u64>,
+    pub refill_target: Option<u64>,
+    pub refill_cooldown: Option<u64>,
+    pub refill_max_per_day: Option<u64>,
+    pub refill_interval: Option<u64>,
+    pub chain_start: Option<i64>,
+    pub chain_id: Option<String>,
+    pub rpc: Option<String>,
+    pub withdraw_limit: Option<u64>,
+    pub auth_key: Option<String>,
+    pub rps: Option<u64>,
+    pub access_list: Option<PathBuf>,
+    pub admin_key: Option<String>,
+    pub api_keys: Option<PathBuf>,
+    pub dry_run: Option<bool>,
+    pub fee_amount: Option<u64>,
+    pub gas_limit: Option<u64>,
+    pub fee_token: Option<String>,
+    pub memo_template: Option<String>,
+    pub campaign: Option<String>,
+    pub airdrop_memo: Option<String>,
+    #[serde(default)]
+    pub allowed_targets: Vec<AddressKind>,
+    pub target_balance_threshold: Option<u64>,
+    pub target_balance_policy: Option<TargetBalancePolicy>,
+    pub require_ownership_proof: Option<bool>,
+    pub reveal_pk: Option<bool>,
+    pub trust_height: Option<u64>,
+    pub trust_hash: Option<String>,
+    pub trusting_period: Option<u64>,
+    #[serde(default)]
+    pub tokens: HashMap<String, String>,
+}
+
+/// Validated configuration the server is started with.
+#[derive(Clone)]
+pub struct Config {
+    pub args: AppConfig,
+    pub port: u16,
+    pub difficulty: u64,
+    pub private_key: SecretKey,
+    pub pool_keys: Vec<SecretKey>,
+    pub dispatch: DispatchStrategy,
+    pub refill_policy: Option<RefillPolicy>,
+    pub chain_start: i64,
+    pub chain_id: ChainId,
+    pub rpc: Url,
+    pub withdraw_limit: u64,
+    pub auth_key: Option<String>,
+    pub rps: Option<u64>,
+    pub access_list_path: Option<PathBuf>,
+    pub access_list: AccessList,
+    pub admin_key: Option<String>,
+    pub api_keys: Vec<ApiKey>,
+    pub dry_run: bool,
+    pub fees: FeeSettings,
+    pub memo_template: String,
+    pub campaign: String,
+    pub airdrop_memo: String,
+    pub allowed_targets: HashSet<AddressKind>,
+    pub target_balance_limit: Option<TargetBalanceLimit>,
+    pub require_ownership_proof: bool,
+    pub reveal_pk: bool,
+    pub trust_root: Option<TrustRoot>,
+    pub tokens: HashMap<String, Address>,
+}
+
+/// Fee parameters of faucet transactions, the SDK defaults are used for
+/// anything not set. `amount` is the price per gas unit in base units of the
+/// fee token.
+#[derive(Clone, Debug, Default)]
+pub struct FeeSettings {
+    pub amount: Option<u64>,
+    pub gas_limit: Option<u64>,
+    pub token: Option<Address>,
+}
+
+/// What to do when the target already holds at least `threshold` whole
+/// tokens of the requested token.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TargetBalancePolicy {
+    /// Refuse the request.
+    #[default]
+    Refuse,
+    /// Only send what brings the target up to the threshold.
+    Cap,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TargetBalanceLimit {
+    pub threshold: u64,
+    pub policy: TargetBalancePolicy,
+}
+
+impl TargetBalanceLimit {
+    /// The threshold in base units, saturating for thresholds the config
+    /// validation rejects.
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+            .checked_mul(10_u64.pow(6))
+            .unwrap_or(u64::MAX)
+    }
+}
+
+/// Trusted block the light client verifies headers from. The trusting
+/// period must be shorter than the chain's unbonding period.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrustRoot {
+    pub height: Height,
+    pub hash: Hash,
+    pub trusting_period: Duration,
+}
+
+/// Refills hot faucet accounts from the treasury account. Amounts are in whole
+/// tokens like the withdraw limit.
+#[derive(Clone, PartialEq)]
+pub struct RefillPolicy {
+    pub treasury_key: SecretKey,
+    pub token: Option<Address>,
+    pub threshold: u64,
+    pub target: u64,
+    pub cooldown: Duration,
+    pub max_per_day: Option<u64>,
+    pub interval: Duration,
+}
+
+impl AppConfig {
+    /// Merges the arguments with the configuration file, if any, and validates
+    /// the result. All problems found are reported at once.
+    pub fn load(&self) -> Result<Config, ConfigError> {
+        let file = match &self.config {
+            Some(path) => FileConfig::read(path).map_err(|e| ConfigError(vec![e]))?,
+            None => FileConfig::default(),
+        };
+
+        let args = self.clone();
+        let merged = FileConfig {
+            port: args.port.or(file.port),
+            difficulty: args.difficulty.or(file.difficulty),
+            private_key: args.private_key.or(file.private_key),
+            private_key_file: args.private_key_file.or(file.private_key_file),
+            wallet_dir: args.wallet_dir.or(file.wallet_dir),
+            wallet_alias: args.wallet_alias.or(file.wallet_alias),
+            wallet_password_file: args.wallet_password_file.or(file.wallet_password_file),
+            keystore: args.keystore.or(file.keystore),
+            keystore_password_file: args.keystore_password_file.or(file.keystore_password_file),
+            pool_key_files: if args.pool_key_files.is_empty() {
+                file.pool_key_files
+            } else {
+                args.pool_key_files
+            },
+            dispatch: args.dispatch.or(file.dispatch),
+            treasury_key_file: args.treasury_key_file.or(file.treasury_key_file),
+            refill_token: args.refill_token.or(file.refill_token),
+            refill_threshold: args.refill_threshold.or(file.refill_threshold),
+            refill_target: args.refill_target.or(file.refill_target),
+            refill_cooldown: args.refill_cooldown.or(file.refill_cooldown),
+            refill_max_per_day: args.refill_max_per_day.or(file.refill_max_per_day),
+            refill_interval: args.refill_interval.or(file.refill_interval),
+            chain_start: args.chain_start.or(file.chain_start),
+            chain_id: args.chain_id.or(file.chain_id),
+            rpc: args.rpc.or(file.rpc),
+            withdraw_limit: args.withdraw_limit.or(file.withdraw_limit),
+            auth_key: args.auth_key.or(file.auth_key),
+            rps: args.rps.or(file.rps),
+            access_list: args.access_list.or(file.access_list),
+            admin_key: args.admin_key.or(file.admin_key),
+            api_keys: args.api_keys.or(file.api_keys),
+            dry_run: Some(args.dry_run || file.dry_run.unwrap_or(false)),
+            fee_amount: args.fee_amount.or(file.fee_amount),
+            gas_limit: args.gas_limit.or(file.gas_limit),
+            fee_token: args.fee_token.or(file.fee_token),
+            memo_template: args.memo_template.or(file.memo_template),
+            campaign: args.campaign.or(file.campaign),
+            airdrop_memo: args.airdrop_memo.or(file.airdrop_memo),
+            allowed_targets: if args.allowed_targets.is_empty() {
+                file.allowed_targets
+            } else {
+                args.allowed_targets
+            },
+            target_balance_threshold: args
+                .target_balance_threshold
+                .or(file.target_balance_threshold),
+            target_balance_policy: args.target_balance_policy.or(file.target_balance_policy),
+            require_ownership_proof: Some(
+                args.require_ownership_proof || file.require_ownership_proof.unwrap_or(false),
+            ),
+            reveal_pk: Some(args.reveal_pk || file.reveal_pk.unwrap_or(false)),
+            trust_height: args.trust_height.or(file.trust_height),
+            trust_hash: args.trust_hash.or(file.trust_hash),
+            trusting_period: args.trusting_period.or(file.trusting_period),
+            tokens: file.tokens,
+        };
+
+        merged.validate(self.clone())
+    }
+}
+
+impl FileConfig {
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("can't read config file {}: {}", path.display(), e))?;
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => toml::from_str(&content).map_err(|e| e.to_string()),
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&content).map_err(|e| e.to_string()),
+            _ => Err(format!(
+                "unsupported config file format {}, expected .toml, .yaml or .yml",
+                path.display()
+            )),
+        }
+        .map_err(|e| format!("invalid config file {}: {}", path.display(), e))
+    }
+
+    pub fn validate(self, args: AppConfig) -> Result<Config, ConfigError> {
+        let mut errors = Vec::new();
+
+        let difficulty = required(self.difficulty, "difficulty", &mut errors);
+        if let Some(difficulty) = difficulty {
+            if difficulty > MAX_DIFFICULTY {
+                errors.push(format!("difficulty must be at most {}", MAX_DIFFICULTY));
+            }
+        }
+
+        let private_key = self.key_source(&mut errors).and_then(|source| {
+            source
+                .load()
+                .map_err(|e| errors.push(format!("can't load the faucet key: {:#}", e)))
+                .ok()
+        });
+
+        let pool_keys = self
+            .pool_key_files
+            .iter()
+            .filter_map(|path| {
+                KeySource::File(path.clone())
+                    .load()
+                    .map_err(|e| {
+                        errors.push(format!("can't load pool key {}: {:#}", path.display(), e))
+                    })
+                    .ok()
+            })
+            .collect();
+
+        let refill_policy = self.refill_policy(&mut errors);
+        let trust_root = self.trust_root(&mut errors);
+
+        let chain_start = required(self.chain_start, "chain_start", &mut errors);
+
+        let chain_id = required(self.chain_id, "chain_id", &mut errors).and_then(|chain_id| {
+            ChainId::from_str(&chain_id)
+                .map_err(|e| errors.push(format!("chain_id is invalid: {}", e)))
+                .ok()
+        });
+
+        let rpc = required(self.rpc, "rpc", &mut errors).and_then(|rpc| {
+            Url::from_str(&rpc)
+                .map_err(|e| errors.push(format!("rpc is not a valid URL: {}", e)))
+                .ok()
+        });
+
+        let withdraw_limit = self.withdraw_limit.unwrap_or(DEFAULT_WITHDRAW_LIMIT);
+        if withdraw_limit == 0 || withdraw_limit.checked_mul(10_u64.pow(6)).is_none() {
+            errors.push("withdraw_limit must be positive and fit in base units".to_string());
+        }
+
+        if let Some(auth_key) = &self.auth_key {
+            if auth_key.len() != AUTH_KEY_LENGTH {
+                errors.push(format!(
+                    "auth_key must be exactly {} bytes long",
+                    AUTH_KEY_LENGTH
+                ));
+            }
+        }
+
+        if self
+            .target_balance_threshold
+            .is_some_and(|threshold| threshold.checked_mul(10_u64.pow(6)).is_none())
+        {
+            errors.push("target_balance_threshold must fit in base units".to_string());
+        }
+
+        if self.rps == Some(0) {
+            errors.push("rps must be positive".to_string());
+        }
+
+        if self.admin_key.as_ref().is_some_and(|key| key.is_empty()) {
+            errors.push("admin_key must not be empty".to_string());
+        }
+
+        let access_list = match &self.access_list {
+            Some(path) if path.exists() => AccessList::load(path)
+                .map_err(|e| errors.push(format!("invalid access list {}: {}", path.display(), e)))
+                .unwrap_or_default(),
+            _ => AccessList::default(),
+        };
+
+        let api_keys = match &self.api_keys {
+            Some(path) => ApiKey::load_all(path)
+                .map_err(|e| errors.push(format!("invalid API keys {}: {}", path.display(), e)))
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        if self.gas_limit == Some(0) {
+            errors.push("gas_limit must be positive".to_string());
+        }
+        let fee_token = self.fee_token.as_ref().and_then(|token| {
+            Address::decode(token)
+                .map_err(|_| errors.push("fee_token has an invalid address".to_string()))
+                .ok()
+        });
+
+        let tokens = self
+            .tokens
+            .iter()
+            .filter_map(|(alias, address)| match Address::decode(address) {
+                Ok(address) => Some((alias.clone(), address)),
+                Err(_) => {
+                    errors.push(format!("token {} has an invalid address", alias));
+                    None
+                }
+            })
+            .collect();
+
+        match (difficulty, private_key, chain_start, chain_id, rpc) {
+            (Some(difficulty), Some(private_key), Some(chain_start), Some(chain_id), Some(rpc))
+                if errors.is_empty() =>
+            {
+                Ok(Config {
+                    args,
+                    port: self.port.unwrap_or(DEFAULT_PORT),
+                    difficulty,
+                    private_key,
+                    pool_keys,
+                    dispatch: self.dispatch.unwrap_or_default(),
+                    refill_policy,
+                    chain_start,
+                    chain_id,
+                    rpc,
+                    withdraw_limit,
+                    auth_key: self.auth_key,
+                    rps: self.rps,
+                    access_list_path: self.access_list,
+                    access_list,
+                    admin_key: self.admin_key,
+                    api_keys,
+                    dry_run: self.dry_run.unwrap_or(false),
+                    fees: FeeSettings {
+                        amount: self.fee_amount,
+                        gas_limit: self.gas_limit,
+                        token: fee_token,
+                    },
+                    memo_template: self
+                        .memo_template
+                        .unwrap_or_else(|| DEFAULT_MEMO_TEMPLATE.to_string()),
+                    campaign: self.campaign.unwrap_or_default(),
+                    airdrop_memo: self
+                        .airdrop_memo
+                        .unwrap_or_else(|| DEFAULT_AIRDROP_MEMO.to_string()),
+                    allowed_targets: if self.allowed_targets.is_empty() {
+                        DEFAULT_ALLOWED_TARGETS.into_iter().collect()
+                    } else {
+                        self.allowed_targets.into_iter().collect()
+                    },
+                    target_balance_limit: self.target_balance_threshold.map(|threshold| {
+                        TargetBalanceLimit {
+                            threshold,
+                            policy: self.target_balance_policy.unwrap_or_default(),
+                        }
+                    }),
+                    require_ownership_proof: self.require_ownership_proof.unwrap_or(false),
+                    reveal_pk: self.reveal_pk.unwrap_or(false),
+                    trust_root,
+                    tokens,
+                })
+            }
+            _ => Err(ConfigError(errors)),
+        }
+    }
+
+    fn refill_policy(&self, errors: &mut Vec<String>) -> Option<RefillPolicy> {
+        let path = self.treasury_key_file.as_ref()?;
+
+        let treasury_key = KeySource::File(path.clone())
+            .load()
+            .map_err(|e| errors.push(format!("can't load the treasury key: {:#}", e)))
+            .ok();
+        let token = self.refill_token.as_ref().and_then(|token| {
+            Address::decode(token)
+                .map_err(|_| errors.push("refill_token has an invalid address".to_string()))
+                .ok()
+        });
+        let threshold = required(self.refill_threshold, "refill_threshold", errors);
+        let target = required(self.refill_target, "refill_target", errors);
+
+        let (treasury_key, threshold, target) = (treasury_key?, threshold?, target?);
+        if target <= threshold {
+            errors.push("refill_target must be greater than refill_threshold".to_string());
+        }
+        if target.checked_mul(10_u64.pow(6)).is_none() {
+            errors.push("refill_target must fit in base units".to_string());
+        }
+        if threshold.checked_mul(10_u64.pow(6)).is_none() {
+            errors.push("refill_threshold must fit in base units".to_string());
+        }
+        if self
+            .refill_max_per_day
+            .is_some_and(|max_per_day| max_per_day.checked_mul(10_u64.pow(6)).is_none())
+        {
+            errors.push("refill_max_per_day must fit in base units".to_string());
+        }
+
+        Some(RefillPolicy {
+            treasury_key,
+            token,
+            threshold,
+            target,
+            cooldown: Duration::from_secs(self.refill_cooldown.unwrap_or(DEFAULT_REFILL_COOLDOWN)),
+            max_per_day: self.refill_max_per_day,
+            interval: Duration::from_secs(self.refill_interval.unwrap_or(DEFAULT_REFILL_INTERVAL)),
+        })
+    }
+
+    fn trust_root(&self, errors: &mut Vec<String>) -> Option<TrustRoot> {
+        let (height, hash) = match (self.trust_height, &self.trust_hash) {
+            (None, None) => return None,
+            (Some(height), Some(hash)) => (height, hash),
+            _ => {
+                errors.push("trust_height and trust_hash must be set together".to_string());
+                return None;
+            }
+        };
+
+        let height = Height::try_from(height)
+            .map_err(|e| errors.push(format!("trust_height is invalid: {}", e)))
+            .ok();
+        let hash = Hash::from_hex_upper(Algorithm::Sha256, &hash.to_uppercase())
+            .map_err(|e| errors.push(format!("trust_hash is invalid: {}", e)))
+            .ok();
+
+        Some(TrustRoot {
+            height: height?,
+            hash: hash?,
+            trusting_period: Duration::from_secs(
+                self.trusting_period.unwrap_or(DEFAULT_TRUSTING_PERIOD),
+            ),
+        })
+    }
+
+    fn key_source(&self, errors: &mut Vec<String>) -> Option<KeySource> {
+        let mut sources = Vec::new();
+
+        if let Some(sk) = &self.private_key {
+            sources.push(KeySource::Raw(sk.clone()));
+        }
+        if let Some(path) = &self.private_key_file {
+            sources.push(KeySource::File(path.clone()));
+        }
+        if let Some(alias) = &self.wallet_alias {
+            sources.push(KeySource::Wallet {
+                dir: self
+                    .wallet_dir
+                    .clone()
+                    .unwrap_or_else(|| PathBuf::from("wallet")),
+                alias: alias.clone(),
+                password_file: self.wallet_password_file.clone(),
+            });
+        }
+        match (&self.keystore, &self.keystore_password_file) {
+            (Some(path), Some(password_file)) => sources.push(KeySource::Keystore {
+                path: path.clone(),
+                password_file: password_file.clone(),
+            }),
+            (Some(_), None) => errors.push("keystore requires keystore_password_file".to_string()),
+            _ => (),
+        }
+
+        match sources.len() {
+            0 => {
+                errors.push(
+                    "one of private_key, private_key_file, wallet_alias or keystore is required"
+                        .to_string(),
+                );
+                None
+            }
+            1 => sources.pop(),
+            _ => {
+                errors.push(
+                    "only one of private_key, private_key_file, wallet_alias or keystore can be set"
+                        .to_string(),
+                );
+                None
+            }
+        }
+    }
+}
+
+impl Config {
+    /// Settings that are only read once at startup, changing any of them
+    /// requires a restart.
+    pub fn structural_changes(&self, other: &Config) -> Vec<&'static str> {
+        let mut changes = Vec::new();
+        if self.port != other.port {
+            changes.push("port");
+        }
+        if self.private_key != other.private_key {
+            changes.push("private_key");
+        }
+        if self.pool_keys != other.pool_keys || self.dispatch != other.dispatch {
+            changes.push("pool_key_files");
+        }
+        match (&self.refill_policy, &other.refill_policy) {
+            (Some(current), Some(new)) if current.treasury_key != new.treasury_key => {
+                changes.push("treasury_key_file");
+            }
+            (Some(current), Some(new)) if current != new => changes.push("refill"),
+            (None, Some(_)) | (Some(_), None) => changes.push("treasury_key_file"),
+            _ => {}
+        }
+        if self.chain_start != other.chain_start {
+            changes.push("chain_start");
+        }
+        if self.chain_id != other.chain_id {
+            changes.push("chain_id");
+        }
+        if self.rpc != other.rpc {
+            changes.push("rpc");
+        }
+        if self.auth_key != other.auth_key {
+            changes.push("auth_key");
+        }
+        if self.admin_key != other.admin_key {
+            changes.push("admin_key");
+        }
+        if self.dry_run != other.dry_run {
+            changes.push("dry_run");
+        }
+        if self.access_list_path != other.access_list_path {
+            changes.push("access_list");
+        }
+        if self.trust_root != other.trust_root {
+            changes.push("trust_root");
+        }
+        changes
+    }
+}
+
+fn required<T>(value: Option<T>, name: &str, errors: &mut Vec<String>) -> Option<T> {
+    if value.is_none() {
+        errors.push(format!("{} is required", name));
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn args() -> AppConfig {
+        AppConfig::parse_from(["namada-faucet"])
+    }
+
+    #[test]
+    fn validate_reports_every_error_at_once() {
+        let file = FileConfig {
+            difficulty: Some(MAX_DIFFICULTY + 1),
+            rpc: Some("not a url".to_string()),
+            rps: Some(0),
+            gas_limit: Some(0),
+            auth_key: Some("short".to_string()),
+            ..FileConfig::default()
+        };
+
+        let ConfigError(errors) = file.validate(args()).err().unwrap();
+
+        for expected in [
+            "difficulty must be at most",
+            "chain_start is required",
+            "chain_id is required",
+            "rpc is not a valid URL",
+            "auth_key must be exactly",
+            "rps must be positive",
+            "gas_limit must be positive",
+        ] {
+            assert!(
+                errors.iter().any(|error| error.starts_with(expected)),
+                "missing {:?} in {:?}",
+                expected,
+                errors
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let error = toml::from_str::<FileConfig>("difficulty = 1\ndificulty = 2\n")
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("unknown field `dificulty`"));
+
+        let error = serde_yaml::from_str::<FileConfig>("difficulty: 1\nrcp: http://localhost\n")
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("unknown field `rcp`"));
+    }
 }
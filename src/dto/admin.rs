@@ -0,0 +1,12 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct FaucetAccountDto {
+    pub alias: String,
+    pub address: String,
+    pub balances: HashMap<String, String>,
+    pub in_flight: u64,
+}
@@ -2,22 +2,29 @@ use std::collections::HashMap;
 
 use data_encoding::HEXLOWER;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use validator::Validate;
 
 use crate::entity::faucet::Faucet;
 
-#[derive(Clone, Serialize, Deserialize, Validate)]
+#[derive(Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct FaucetRequestDto {
     #[validate(length(min = 1, max = 128, message = "Invalid solution"))]
-    pub solution: String,
+    pub solution: Option<String>,
     #[validate(length(equal = 32, message = "Invalid challenge"))]
-    pub challenge: String,
+    pub challenge: Option<String>,
     #[validate(length(equal = 64, message = "Invalid proof"))]
-    pub tag: String,
+    pub tag: Option<String>,
     pub transfer: Transfer,
+    #[validate(length(min = 1, max = 256, message = "Invalid memo"))]
+    pub memo: Option<String>,
+    #[validate(length(min = 1, max = 128, message = "Invalid public key"))]
+    pub public_key: Option<String>,
+    #[validate(length(min = 1, max = 256, message = "Invalid signature"))]
+    pub signature: Option<String>,
 }
 
-#[derive(Clone, Serialize, Deserialize, Validate)]
+#[derive(Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct Transfer {
     #[validate(length(min = 1, max = 50, message = "Invalid token address"))]
     pub token: String,
@@ -27,7 +34,7 @@ pub struct Transfer {
     pub amount: u64,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 pub struct FaucetResponseDto {
     pub challenge: String,
     pub tag: String,
@@ -42,20 +49,68 @@ impl From<Faucet> for FaucetResponseDto {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 pub struct FaucetResponseStatusDto {
+    pub request_id: String,
     pub token: String,
     pub amount: u64,
     pub target: String,
     pub sent: bool,
     pub tx_hash: Option<String>,
+    pub fee_estimate: Option<String>,
+    #[serde(default)]
+    pub dry_run: bool,
+    pub reveal_pk_tx_hash: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 pub struct FaucetSettingResponse {
     pub difficulty: u64,
     pub chain_id: String,
     pub start_at: i64,
     pub withdraw_limit: u64,
     pub tokens_alias_to_address: HashMap<String, String>,
+    #[serde(default)]
+    pub ownership_proof: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransferStatusDto {
+    Queued,
+    Broadcast,
+    Applied,
+    Failed,
+}
+
+impl TransferStatusDto {
+    pub fn is_final(&self) -> bool {
+        matches!(self, TransferStatusDto::Applied | TransferStatusDto::Failed)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct TransferEventDto {
+    pub request_id: String,
+    pub status: TransferStatusDto,
+    pub tx_hash: Option<String>,
+    pub height: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TransferEventsQuery {
+    pub idempotency_key: String,
+}
+
+impl TransferEventDto {
+    pub fn new(request_id: &str, status: TransferStatusDto) -> Self {
+        Self {
+            request_id: request_id.to_string(),
+            status,
+            tx_hash: None,
+            height: None,
+            error: None,
+        }
+    }
 }
@@ -1 +1,2 @@
+pub mod admin;
 pub mod faucet;
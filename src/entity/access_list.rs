@@ -0,0 +1,86 @@
+use std::{collections::HashSet, fs, net::IpAddr, path::Path};
+
+use ipnet::IpNet;
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct AccessList {
+    #[serde(default)]
+    pub deny: AccessRules,
+    #[serde(default)]
+    pub allow: AccessRules,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct AccessRules {
+    #[serde(default)]
+    pub addresses: HashSet<String>,
+    #[serde(default, deserialize_with = "deserialize_ip_ranges")]
+    #[schema(value_type = Vec<String>, example = json!(["10.0.0.0/8"]))]
+    pub ips: Vec<IpNet>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessDecision {
+    Allowed,
+    Default,
+    Denied,
+}
+
+impl AccessList {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Deny entries always win over allow entries, so an address or IP that
+    /// shows up in both lists is blocked.
+    pub fn check(&self, address: Option<&str>, ip: Option<IpAddr>) -> AccessDecision {
+        if self.deny.matches(address, ip) {
+            AccessDecision::Denied
+        } else if self.allow.matches(address, ip) {
+            AccessDecision::Allowed
+        } else {
+            AccessDecision::Default
+        }
+    }
+}
+
+impl AccessRules {
+    pub fn matches(&self, address: Option<&str>, ip: Option<IpAddr>) -> bool {
+        let address_match = address.map_or(false, |address| self.addresses.contains(address));
+        let ip_match = ip.map_or(false, |ip| self.ips.iter().any(|range| range.contains(&ip)));
+
+        address_match || ip_match
+    }
+}
+
+pub fn parse_ip_range(value: &str) -> Option<IpNet> {
+    value
+        .parse::<IpNet>()
+        .ok()
+        .or_else(|| value.parse::<IpAddr>().ok().map(IpNet::from))
+}
+
+fn deserialize_ip_ranges<'de, D>(deserializer: D) -> Result<Vec<IpNet>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values = Vec::<String>::deserialize(deserializer)?;
+
+    values
+        .iter()
+        .map(|value| {
+            parse_ip_range(value).ok_or_else(|| {
+                serde::de::Error::custom(format!("invalid IP address or CIDR range: {}", value))
+            })
+        })
+        .collect()
+}
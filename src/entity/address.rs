@@ -0,0 +1,35 @@
+use std::fmt;
+
+use namada_sdk::address::Address;
+use serde::Deserialize;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AddressKind {
+    Implicit,
+    Established,
+    Internal,
+}
+
+pub const DEFAULT_ALLOWED_TARGETS: [AddressKind; 2] =
+    [AddressKind::Implicit, AddressKind::Established];
+
+impl From<&Address> for AddressKind {
+    fn from(address: &Address) -> Self {
+        match address {
+            Address::Implicit(_) => AddressKind::Implicit,
+            Address::Established(_) => AddressKind::Established,
+            Address::Internal(_) => AddressKind::Internal,
+        }
+    }
+}
+
+impl fmt::Display for AddressKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddressKind::Implicit => write!(f, "implicit"),
+            AddressKind::Established => write!(f, "established"),
+            AddressKind::Internal => write!(f, "internal"),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use data_encoding::HEXLOWER;
+use orion::hazardous::hash::sha2::sha256::Sha256;
+use serde::{Deserialize, Serialize};
+
+/// An API key issued to a trusted integrator. Only the SHA-256 digest of the
+/// key is ever stored, the plaintext key is handed out once when issued.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ApiKey {
+    pub name: String,
+    pub key_hash: String,
+    #[serde(default)]
+    pub daily_quota: Option<u64>,
+    #[serde(default)]
+    pub allowed_tokens: Option<HashSet<String>>,
+    #[serde(default)]
+    pub max_amount: Option<u64>,
+    #[serde(default)]
+    pub allow_custom_memo: bool,
+}
+
+impl ApiKey {
+    pub fn load_all(path: &Path) -> anyhow::Result<Vec<Self>> {
+        let content = fs::read_to_string(path)?;
+        let api_keys: Vec<Self> = serde_json::from_str(&content)?;
+        for api_key in &api_keys {
+            if api_key.max_amount.is_some() && api_key.max_amount().is_none() {
+                anyhow::bail!("max_amount of API key {} is too large", api_key.name);
+            }
+        }
+        Ok(api_keys)
+    }
+
+    pub fn hash(key: &str) -> String {
+        let digest = Sha256::digest(key.as_bytes()).expect("Should be able to hash bytes");
+        HEXLOWER.encode(digest.as_ref())
+    }
+
+    pub fn allows_token(&self, token: &str) -> bool {
+        self.allowed_tokens
+            .as_ref()
+            .map_or(true, |tokens| tokens.contains(token))
+    }
+
+    /// Maximum amount in base units, `max_amount` is configured in whole tokens
+    /// like the global withdraw limit.
+    pub fn max_amount(&self) -> Option<u64> {
+        self.max_amount
+            .and_then(|amount| amount.checked_mul(10_u64.pow(6)))
+    }
+}
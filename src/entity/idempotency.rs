@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+
+use crate::dto::faucet::FaucetResponseStatusDto;
+
+/// A transfer request made with an `Idempotency-Key`. `response` is set once
+/// the request has been handled.
+#[derive(Clone, Debug)]
+pub struct IdempotencyRecord {
+    pub fingerprint: String,
+    pub created_at: DateTime<Utc>,
+    pub response: Option<FaucetResponseStatusDto>,
+}
+
+impl IdempotencyRecord {
+    pub fn new(fingerprint: String) -> Self {
+        Self {
+            fingerprint,
+            created_at: Utc::now(),
+            response: None,
+        }
+    }
+}
+
+/// What to do with a request carrying an idempotency key.
+#[derive(Clone, Debug)]
+pub enum IdempotencyStatus {
+    /// First time the key is seen, the request must be handled.
+    New,
+    /// The request was already handled, this is its response.
+    Done(FaucetResponseStatusDto),
+    /// The same request is still being handled.
+    InFlight,
+    /// The key was used for a different request.
+    Mismatch,
+}
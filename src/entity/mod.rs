@@ -1 +1,5 @@
+pub mod access_list;
+pub mod address;
+pub mod api_key;
 pub mod faucet;
+pub mod idempotency;
@@ -0,0 +1,5 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("invalid configuration: {}", .0.join("; "))]
+pub struct ConfigError(pub Vec<String>);
@@ -1,10 +1,14 @@
-use crate::response::api::ApiErrorResponse;
+use crate::{entity::address::AddressKind, response::api::ApiErrorResponse};
 use axum::{
-    http::StatusCode,
+    http::{header::RETRY_AFTER, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
 };
 use thiserror::Error;
 
+/// Seconds a client should wait before asking again for the result of a
+/// request that is still being processed.
+const IN_FLIGHT_RETRY_AFTER: &str = "1";
+
 #[derive(Error, Debug)]
 pub enum FaucetError {
     #[error("Invalid Proof of Work")]
@@ -23,6 +27,38 @@ pub enum FaucetError {
     SdkError(String),
     #[error("Withdraw limit must be less then {0}")]
     InvalidWithdrawLimit(u64),
+    #[error("Request blocked by faucet access list")]
+    Blocked,
+    #[error("Missing or invalid admin key")]
+    Unauthorized,
+    #[error("Internal error: {0}")]
+    InternalError(String),
+    #[error("Unknown request")]
+    UnknownRequest,
+    #[error("Invalid idempotency key")]
+    InvalidIdempotencyKey,
+    #[error("A request with this idempotency key is still being processed")]
+    IdempotencyKeyInUse,
+    #[error("Idempotency key was already used for a different request")]
+    IdempotencyKeyMismatch,
+    #[error("Invalid API key")]
+    InvalidApiKey,
+    #[error("Token not allowed")]
+    TokenNotAllowed,
+    #[error("API key quota exceeded")]
+    QuotaExceeded,
+    #[error("Custom memos are not allowed")]
+    MemoNotAllowed,
+    #[error("Transfers to {0} addresses are not allowed")]
+    TargetNotAllowed(AddressKind),
+    #[error("Target address belongs to the faucet")]
+    FaucetTarget,
+    #[error("Target already holds {0}")]
+    TargetWellFunded(String),
+    #[error("Invalid proof of ownership of the target address")]
+    InvalidOwnershipProof,
+    #[error("Public key doesn't match the target address")]
+    InvalidPublicKey,
 }
 
 impl IntoResponse for FaucetError {
@@ -36,8 +72,34 @@ impl IntoResponse for FaucetError {
             FaucetError::InvalidWithdrawLimit(_) => StatusCode::BAD_REQUEST,
             FaucetError::FaucetOutOfBalance => StatusCode::CONFLICT,
             FaucetError::SdkError(_) => StatusCode::BAD_REQUEST,
+            FaucetError::Blocked => StatusCode::FORBIDDEN,
+            FaucetError::Unauthorized => StatusCode::UNAUTHORIZED,
+            FaucetError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            FaucetError::UnknownRequest => StatusCode::NOT_FOUND,
+            FaucetError::InvalidIdempotencyKey => StatusCode::BAD_REQUEST,
+            FaucetError::IdempotencyKeyInUse => StatusCode::CONFLICT,
+            FaucetError::IdempotencyKeyMismatch => StatusCode::UNPROCESSABLE_ENTITY,
+            FaucetError::InvalidApiKey => StatusCode::UNAUTHORIZED,
+            FaucetError::TokenNotAllowed => StatusCode::FORBIDDEN,
+            FaucetError::QuotaExceeded => StatusCode::TOO_MANY_REQUESTS,
+            FaucetError::MemoNotAllowed => StatusCode::FORBIDDEN,
+            FaucetError::TargetNotAllowed(_) => StatusCode::BAD_REQUEST,
+            FaucetError::FaucetTarget => StatusCode::BAD_REQUEST,
+            FaucetError::TargetWellFunded(_) => StatusCode::CONFLICT,
+            FaucetError::InvalidOwnershipProof => StatusCode::FORBIDDEN,
+            FaucetError::InvalidPublicKey => StatusCode::BAD_REQUEST,
         };
 
-        ApiErrorResponse::send(status_code.as_u16(), Some(self.to_string()))
+        // The first request with the key is still running, clients can ask
+        // again for its result.
+        let retry_after = matches!(self, FaucetError::IdempotencyKeyInUse);
+
+        let mut response = ApiErrorResponse::send(status_code.as_u16(), Some(self.to_string()));
+        if retry_after {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from_static(IN_FLIGHT_RETRY_AFTER));
+        }
+        response
     }
 }
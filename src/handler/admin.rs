@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use axum::{extract::State, http::HeaderMap, Json};
+
+use crate::{
+    dto::admin::FaucetAccountDto,
+    entity::access_list::AccessList,
+    error::{api::ApiError, faucet::FaucetError},
+    repository::access_list::AccessListRepositoryTrait,
+    response::api::ApiErrorResponse,
+    state::faucet::FaucetState,
+};
+
+const ADMIN_KEY_HEADER: &str = "x-admin-key";
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/access-list",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Current access list", body = AccessList),
+        (status = 401, description = "Missing or wrong admin key", body = ApiErrorResponse),
+    )
+)]
+pub async fn get_access_list(
+    State(state): State<FaucetState>,
+    headers: HeaderMap,
+) -> Result<Json<AccessList>, ApiError> {
+    authorize(&state, &headers)?;
+
+    let access_list = state.access_list_repo.get().await;
+
+    Ok(Json(access_list))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/access-list",
+    tag = "admin",
+    request_body = AccessList,
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Updated access list", body = AccessList),
+        (status = 401, description = "Missing or wrong admin key", body = ApiErrorResponse),
+    )
+)]
+pub async fn update_access_list(
+    State(mut state): State<FaucetState>,
+    headers: HeaderMap,
+    Json(access_list): Json<AccessList>,
+) -> Result<Json<AccessList>, ApiError> {
+    authorize(&state, &headers)?;
+
+    if let Some(path) = &state.access_list_path {
+        access_list
+            .save(path)
+            .map_err(|e| FaucetError::InternalError(e.to_string()))?;
+    }
+    state.access_list_repo.set(access_list.clone()).await;
+
+    tracing::info!("Access list updated");
+
+    Ok(Json(access_list))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/accounts",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Faucet accounts and their balances", body = [FaucetAccountDto]),
+        (status = 401, description = "Missing or wrong admin key", body = ApiErrorResponse),
+    )
+)]
+pub async fn get_accounts(
+    State(state): State<FaucetState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<FaucetAccountDto>>, ApiError> {
+    authorize(&state, &headers)?;
+
+    let accounts = faucet_accounts(&state).await?;
+
+    Ok(Json(accounts))
+}
+
+/// Balances of every pool account for each configured token, or the native
+/// token when no token registry is configured.
+pub(crate) async fn faucet_accounts(
+    state: &FaucetState,
+) -> Result<Vec<FaucetAccountDto>, FaucetError> {
+    let mut tokens = state.settings.read().await.tokens.clone();
+    if tokens.is_empty() {
+        let nam_token_address = state
+            .ledger
+            .native_token()
+            .await
+            .map_err(|e| FaucetError::SdkError(e.to_string()))?;
+        tokens.insert("NAM".to_string(), nam_token_address);
+    }
+
+    let mut accounts = Vec::new();
+    for account in state.accounts.accounts() {
+        let mut balances = HashMap::new();
+        for (alias, token) in &tokens {
+            let balance = state
+                .ledger
+                .balance(token, &account.address)
+                .await
+                .map_err(|e| FaucetError::SdkError(e.to_string()))?;
+            balances.insert(alias.clone(), balance.to_string());
+        }
+
+        accounts.push(FaucetAccountDto {
+            alias: account.alias.clone(),
+            address: account.address.to_string(),
+            balances,
+            in_flight: account.in_flight(),
+        });
+    }
+
+    Ok(accounts)
+}
+
+/// Admin routes are disabled unless an admin key has been configured.
+fn authorize(state: &FaucetState, headers: &HeaderMap) -> Result<(), FaucetError> {
+    let admin_key = state.admin_key.as_ref().ok_or(FaucetError::Unauthorized)?;
+    let provided_key = headers
+        .get(ADMIN_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(FaucetError::Unauthorized)?;
+
+    if orion::util::secure_cmp(admin_key.as_bytes(), provided_key.as_bytes()).is_ok() {
+        Ok(())
+    } else {
+        Err(FaucetError::Unauthorized)
+    }
+}
@@ -1,51 +1,112 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap, convert::Infallible, net::SocketAddr, str::FromStr, time::Duration,
+};
 
-use axum::{extract::State, Json};
-use axum_macros::debug_handler;
-use namada_sdk::{
-    address::Address,
-    args::{InputAmount, TxTransparentTransferData},
-    io::NullIo,
-    rpc,
-    signing::default_sign,
-    tendermint::abci::Code,
-    tx::data::ResultCode,
-    Namada,
+use axum::{
+    extract::{ConnectInfo, Path, Query, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+    Json,
 };
+use axum_macros::debug_handler;
+use data_encoding::HEXLOWER;
+use futures::{stream, Stream, StreamExt};
+use namada_sdk::{address::Address, key::common::PublicKey, token::Amount};
+use orion::hazardous::hash::sha2::sha256::Sha256;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
 
 use crate::{
+    config::TargetBalancePolicy,
     dto::faucet::{
         FaucetRequestDto, FaucetResponseDto, FaucetResponseStatusDto, FaucetSettingResponse,
+        TransferEventDto, TransferEventsQuery, TransferStatusDto,
+    },
+    entity::{
+        access_list::AccessDecision, address::AddressKind, api_key::ApiKey,
+        idempotency::IdempotencyStatus,
     },
     error::{api::ApiError, faucet::FaucetError, validate::ValidatedRequest},
-    repository::faucet::FaucetRepositoryTrait,
-    state::faucet::FaucetState,
+    repository::{
+        access_list::AccessListRepositoryTrait, api_key::ApiKeyRepositoryTrait,
+        faucet::FaucetRepositoryTrait, idempotency::IdempotencyRepositoryTrait,
+    },
+    response::api::ApiErrorResponse,
+    sdk::{
+        error::NamadaError,
+        ledger::{Transfer, TxOutcome, TxStatus},
+    },
+    state::{events::TransferEvents, faucet::FaucetState},
+    utils::memo::{render_memo, MemoContext},
 };
 
+const API_KEY_HEADER: &str = "x-api-key";
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+/// Transfers not applied by the time they were submitted are followed for
+/// `TX_STATUS_POLLS * TX_STATUS_INTERVAL`.
+const TX_STATUS_INTERVAL: Duration = Duration::from_secs(2);
+const TX_STATUS_POLLS: u32 = 60;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/faucet/setting",
+    tag = "faucet",
+    responses(
+        (status = 200, description = "Faucet settings", body = FaucetSettingResponse),
+        (status = 400, description = "Ledger query failed", body = ApiErrorResponse),
+    )
+)]
 pub async fn faucet_settings(
     State(state): State<FaucetState>,
 ) -> Result<Json<FaucetSettingResponse>, ApiError> {
-    let nam_token_address = rpc::query_native_token(&state.sdk.clone_client())
-        .await
-        .unwrap();
+    let settings = state.settings.read().await.clone();
+
+    let tokens_alias_to_address = if settings.tokens.is_empty() {
+        let nam_token_address = state
+            .ledger
+            .native_token()
+            .await
+            .map_err(|e| FaucetError::SdkError(e.to_string()))?;
+
+        HashMap::from([("NAM".to_string(), nam_token_address.to_string())])
+    } else {
+        settings
+            .tokens
+            .iter()
+            .map(|(alias, address)| (alias.clone(), address.to_string()))
+            .collect()
+    };
 
     let response = FaucetSettingResponse {
-        difficulty: state.difficulty,
+        difficulty: settings.difficulty,
         chain_id: state.chain_id,
         start_at: state.chain_start,
-        withdraw_limit: state.withdraw_limit,
-        tokens_alias_to_address: HashMap::from([(
-            "NAM".to_string(),
-            nam_token_address.to_string(),
-        )]),
+        withdraw_limit: settings.withdraw_limit,
+        tokens_alias_to_address,
+        ownership_proof: settings.require_ownership_proof,
     };
 
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/faucet",
+    tag = "faucet",
+    responses(
+        (status = 200, description = "A new proof of work challenge", body = FaucetResponseDto),
+        (status = 403, description = "Caller is blocked", body = ApiErrorResponse),
+    )
+)]
 pub async fn request_challenge(
     State(mut state): State<FaucetState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
 ) -> Result<Json<FaucetResponseDto>, ApiError> {
+    let access = state.access_list_repo.check(None, Some(addr.ip())).await;
+    if access == AccessDecision::Denied {
+        return Err(FaucetError::Blocked.into());
+    }
+
     let faucet_request = state
         .faucet_service
         .generate_faucet_request(state.auth_key)
@@ -55,18 +116,96 @@ pub async fn request_challenge(
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/faucet",
+    tag = "faucet",
+    request_body = FaucetRequestDto,
+    params(
+        ("x-api-key" = Option<String>, Header, description = "API key, replaces the proof of work"),
+        ("idempotency-key" = Option<String>, Header, description = "Repeated requests with the same key get the original response"),
+    ),
+    responses(
+        (status = 200, description = "Transfer result", body = FaucetResponseStatusDto),
+        (status = 400, description = "Invalid request or solution", body = ApiErrorResponse),
+        (status = 401, description = "Unknown API key", body = ApiErrorResponse),
+        (status = 403, description = "Invalid challenge tag, blocked caller or disallowed token", body = ApiErrorResponse),
+        (status = 409, description = "Challenge already used, faucet out of balance, target well funded or idempotency key in use", body = ApiErrorResponse),
+        (status = 422, description = "Idempotency key used for a different request", body = ApiErrorResponse),
+        (status = 429, description = "API key quota exceeded", body = ApiErrorResponse),
+    )
+)]
 #[debug_handler]
 pub async fn request_transfer(
     State(mut state): State<FaucetState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     ValidatedRequest(payload): ValidatedRequest<FaucetRequestDto>,
 ) -> Result<Json<FaucetResponseStatusDto>, ApiError> {
-    let auth_key: String = state.auth_key.clone();
-    let client = state.sdk.clone_client();
+    let idempotency_key = match headers.get(IDEMPOTENCY_KEY_HEADER) {
+        Some(value) => {
+            let key = value
+                .to_str()
+                .ok()
+                .filter(|key| !key.is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LENGTH)
+                .ok_or(FaucetError::InvalidIdempotencyKey)?;
+            Some(key.to_string())
+        }
+        None => None,
+    };
 
-    if payload.transfer.amount > state.withdraw_limit {
-        return Err(FaucetError::InvalidWithdrawLimit(state.withdraw_limit).into());
+    if let Some(key) = &idempotency_key {
+        let fingerprint = request_fingerprint(&headers, &payload);
+        match state.idempotency_repo.begin(key, &fingerprint).await {
+            IdempotencyStatus::New => (),
+            IdempotencyStatus::Done(response) => return Ok(Json(response)),
+            IdempotencyStatus::InFlight => return Err(FaucetError::IdempotencyKeyInUse.into()),
+            IdempotencyStatus::Mismatch => return Err(FaucetError::IdempotencyKeyMismatch.into()),
+        }
     }
 
+    // The transfer runs on its own task so that it completes, and its result
+    // is recorded, even if the client goes away.
+    let result = tokio::spawn(process_transfer(
+        state.clone(),
+        addr,
+        headers,
+        payload,
+        idempotency_key.clone(),
+    ))
+    .await
+    .unwrap_or_else(|e| Err(FaucetError::InternalError(e.to_string()).into()));
+
+    if let Some(key) = &idempotency_key {
+        match &result {
+            Ok(response) => state.idempotency_repo.complete(key, response.clone()).await,
+            Err(_) => state.idempotency_repo.abort(key).await,
+        }
+    }
+
+    result.map(Json)
+}
+
+async fn process_transfer(
+    mut state: FaucetState,
+    addr: SocketAddr,
+    headers: HeaderMap,
+    payload: FaucetRequestDto,
+    idempotency_key: Option<String>,
+) -> Result<FaucetResponseStatusDto, ApiError> {
+    let auth_key: String = state.auth_key.clone();
+    let settings = state.settings.read().await.clone();
+    let request_id = state.faucet_service.generate_request_id();
+
+    let api_key = match headers.get(API_KEY_HEADER) {
+        Some(value) => {
+            let key = value.to_str().map_err(|_| FaucetError::InvalidApiKey)?;
+            let api_key = state.api_key_repo.find(&ApiKey::hash(key)).await;
+            Some(api_key.ok_or(FaucetError::InvalidApiKey)?)
+        }
+        None => None,
+    };
+
     let token_address = Address::decode(payload.transfer.token.clone());
     let token_address = if let Ok(address) = token_address {
         address
@@ -80,99 +219,516 @@ pub async fn request_transfer(
         return Err(FaucetError::InvalidAddress.into());
     };
 
-    if state.faucet_repo.contains(&payload.challenge).await {
-        return Err(FaucetError::DuplicateChallenge.into());
+    let target_kind = AddressKind::from(&target_address);
+    if !settings.allowed_targets.contains(&target_kind) {
+        return Err(FaucetError::TargetNotAllowed(target_kind).into());
     }
-    let is_valid_proof =
-        state
-            .faucet_service
-            .verify_tag(&auth_key, &payload.challenge, &payload.tag);
-    if !is_valid_proof {
-        return Err(FaucetError::InvalidProof.into());
+    if state.accounts.contains(&target_address) || state.treasury.as_ref() == Some(&target_address)
+    {
+        return Err(FaucetError::FaucetTarget.into());
     }
 
-    let is_valid_pow =
-        state
-            .faucet_service
-            .verify_pow(&payload.challenge, &payload.solution, state.difficulty);
-    if !is_valid_pow {
-        return Err(FaucetError::InvalidPoW.into());
+    if !settings.tokens.is_empty()
+        && !settings
+            .tokens
+            .values()
+            .any(|token| token == &token_address)
+    {
+        return Err(FaucetError::TokenNotAllowed.into());
     }
 
-    let faucet_address = state.faucet_address.clone();
+    let access = state
+        .access_list_repo
+        .check(Some(&target_address.to_string()), Some(addr.ip()))
+        .await;
+    if access == AccessDecision::Denied {
+        return Err(FaucetError::Blocked.into());
+    }
 
-    if let Ok(balance) =
-        rpc::get_token_balance(&client, &token_address, &faucet_address, None).await
-    {
-        if balance < payload.transfer.amount.into() {
-            return Err(FaucetError::FaucetOutOfBalance.into());
+    let withdraw_limit = api_key
+        .as_ref()
+        .and_then(|api_key| api_key.max_amount())
+        .unwrap_or(settings.withdraw_limit);
+    if access != AccessDecision::Allowed && payload.transfer.amount > withdraw_limit {
+        return Err(FaucetError::InvalidWithdrawLimit(withdraw_limit).into());
+    }
+
+    // Requests authenticated with an API key skip the challenge and proof of
+    // work, everything else still applies to them.
+    let challenge = if let Some(api_key) = &api_key {
+        if !api_key.allows_token(&token_address.to_string()) {
+            return Err(FaucetError::TokenNotAllowed.into());
         }
+        None
     } else {
-        return Err(FaucetError::SdkError("Can't query faucet balance".to_string()).into());
-    }
+        let (challenge, solution, tag) = match (&payload.challenge, &payload.solution, &payload.tag)
+        {
+            (Some(challenge), Some(solution), Some(tag)) => (challenge, solution, tag),
+            _ => return Err(FaucetError::InvalidProof.into()),
+        };
 
-    let denominated_amount = rpc::denominate_amount(
-        &client,
-        &NullIo,
-        &token_address,
-        payload.transfer.amount.into(),
-    )
-    .await;
+        if state.faucet_repo.contains(challenge).await {
+            return Err(FaucetError::DuplicateChallenge.into());
+        }
+        let is_valid_proof = state.faucet_service.verify_tag(&auth_key, challenge, tag);
+        if !is_valid_proof {
+            return Err(FaucetError::InvalidProof.into());
+        }
 
-    let transfer = TxTransparentTransferData {
-        source: faucet_address,
-        target: target_address,
-        token: token_address.clone(),
-        amount: InputAmount::Unvalidated(denominated_amount),
+        let is_valid_pow =
+            state
+                .faucet_service
+                .verify_pow(challenge, solution, settings.difficulty);
+        if !is_valid_pow {
+            return Err(FaucetError::InvalidPoW.into());
+        }
+
+        Some(challenge.clone())
     };
 
-    let mut transfer_tx_builder = state.sdk.new_transparent_transfer(vec![transfer]);
+    // Only whoever controls the target can ask for funds to it.
+    if let (Some(challenge), true) = (&challenge, settings.require_ownership_proof) {
+        let is_owner = match (&payload.public_key, &payload.signature) {
+            (Some(public_key), Some(signature)) => state.faucet_service.verify_ownership(
+                challenge,
+                &target_address,
+                public_key,
+                signature,
+            ),
+            _ => false,
+        };
+        if !is_owner {
+            return Err(FaucetError::InvalidOwnershipProof.into());
+        }
+    }
 
-    transfer_tx_builder.tx.memo = Some("Transfer from faucet".to_string().as_bytes().to_vec());
+    // Targets that already hold enough of the token are refused or only
+    // topped up to the threshold. This queries the ledger, so it only runs
+    // for authenticated requests.
+    let mut transfer_amount = payload.transfer.amount;
+    if let (Some(limit), false) = (
+        settings.target_balance_limit,
+        access == AccessDecision::Allowed,
+    ) {
+        let target_balance = state
+            .ledger
+            .balance(&token_address, &target_address)
+            .await
+            .map_err(|_| FaucetError::SdkError("Can't query target balance".to_string()))?;
+        let threshold = Amount::from(limit.threshold());
+        if target_balance >= threshold {
+            let held = state
+                .ledger
+                .denominate(&token_address, target_balance)
+                .await;
+            return Err(FaucetError::TargetWellFunded(held).into());
+        }
+        if limit.policy == TargetBalancePolicy::Cap {
+            let missing = threshold
+                .checked_sub(target_balance)
+                .unwrap_or_default()
+                .raw_amount()
+                .low_u64();
+            transfer_amount = transfer_amount.min(missing);
+        }
+    }
 
-    let (mut transfer_tx, signing_data) = transfer_tx_builder
-        .build(&*state.sdk)
-        .await
-        .expect("unable to build transfer");
-    state
-        .sdk
-        .sign(
-            &mut transfer_tx,
-            &transfer_tx_builder.tx,
-            signing_data,
-            default_sign,
-            (),
-        )
+    // The target's public key is revealed after funding it, so that it can
+    // transact right away.
+    let reveal_public_key = match (&payload.public_key, settings.reveal_pk) {
+        (Some(public_key), true) => {
+            let public_key =
+                PublicKey::from_str(public_key).map_err(|_| FaucetError::InvalidPublicKey)?;
+            if Address::from(&public_key) != target_address {
+                return Err(FaucetError::InvalidPublicKey.into());
+            }
+            Some(public_key)
+        }
+        _ => None,
+    };
+
+    let memo = match (&payload.memo, &api_key) {
+        (Some(memo), Some(api_key)) if api_key.allow_custom_memo => memo.clone(),
+        (Some(_), _) => return Err(FaucetError::MemoNotAllowed.into()),
+        (None, _) => render_memo(
+            &settings.memo_template,
+            &MemoContext {
+                request_id: &request_id,
+                chain_id: &state.chain_id,
+                campaign: &settings.campaign,
+                target: &payload.transfer.target,
+                token: &payload.transfer.token,
+                amount: transfer_amount,
+            },
+        ),
+    };
+
+    let fee = state
+        .ledger
+        .estimate_fee(&settings.fees)
         .await
-        .expect("unable to sign reveal pk tx");
+        .map_err(|e| FaucetError::SdkError(e.to_string()))?;
 
-    let process_tx_response = state.sdk.submit(transfer_tx, &transfer_tx_builder.tx).await;
+    // Revealing the target's key is a second transaction paid by the faucet.
+    let reveal_public_key = match reveal_public_key {
+        Some(public_key) => {
+            let revealed = state
+                .ledger
+                .is_public_key_revealed(&target_address)
+                .await
+                .unwrap_or(true);
+            (!revealed).then_some(public_key)
+        }
+        None => None,
+    };
+    let total_fee = if reveal_public_key.is_some() {
+        fee.amount
+            .checked_add(fee.amount)
+            .ok_or(FaucetError::FaucetOutOfBalance)?
+    } else {
+        fee.amount
+    };
 
-    let (transfer_result, tx_hash) = if let Ok(response) = process_tx_response {
-        match response {
-            namada_sdk::tx::ProcessTxResponse::Applied(r) => {
-                (r.code.eq(&ResultCode::Ok), Some(r.hash.to_string()))
-            }
-            namada_sdk::tx::ProcessTxResponse::Broadcast(r) => {
-                (r.code.eq(&Code::Ok), Some(r.hash.to_string()))
+    // The faucet pays the fees, so it needs enough balance for both the
+    // transfer and the fees.
+    let amount = Amount::from(transfer_amount);
+    let required_balance = if fee.token == token_address {
+        amount
+            .checked_add(total_fee)
+            .ok_or(FaucetError::FaucetOutOfBalance)?
+    } else {
+        amount
+    };
+
+    // Accounts that ran dry are passed over until the treasury refills them.
+    let mut underfunded = Vec::new();
+    let account = loop {
+        let account = state
+            .accounts
+            .acquire_except(&underfunded)
+            .ok_or(FaucetError::FaucetOutOfBalance)?;
+        let faucet_address = &account.account.address;
+
+        let has_fee = fee.token == token_address
+            || faucet_balance(&state, &fee.token, faucet_address).await? >= total_fee;
+        let has_amount = has_fee
+            && faucet_balance(&state, &token_address, faucet_address).await? >= required_balance;
+        if has_amount {
+            break account;
+        }
+        underfunded.push(faucet_address.clone());
+    };
+    let faucet_address = account.account.address.clone();
+
+    // Concurrent requests with the same challenge got past the check above,
+    // only the first one to reserve it goes through.
+    if let Some(challenge) = &challenge {
+        if !state.faucet_repo.reserve(challenge.clone()).await {
+            return Err(FaucetError::DuplicateChallenge.into());
+        }
+    }
+
+    if let Some(api_key) = &api_key {
+        if !state.api_key_repo.reserve_quota(api_key).await {
+            if let Some(challenge) = &challenge {
+                state.faucet_repo.release(challenge).await;
             }
-            _ => (false, None),
+            return Err(FaucetError::QuotaExceeded.into());
+        }
+    }
+
+    let fee_estimate = state.ledger.denominate(&fee.token, fee.amount).await;
+
+    let transfer = Transfer {
+        source: faucet_address,
+        target: target_address.clone(),
+        token: token_address.clone(),
+        amount,
+    };
+    if !state.dry_run {
+        if let Some(key) = &idempotency_key {
+            state.events.alias(key, &request_id);
+        }
+        state.events.publish(TransferEventDto::new(
+            &request_id,
+            TransferStatusDto::Queued,
+        ));
+    }
+    let outcome = state
+        .ledger
+        .transfer(
+            &account.account.public_key,
+            transfer,
+            &memo,
+            &settings.fees,
+            state.dry_run,
+        )
+        .await;
+    if !state.dry_run {
+        track_transfer(&state, &request_id, &outcome);
+    }
+    let (transfer_result, tx_hash) = match outcome {
+        Ok(outcome) => (outcome.applied, Some(outcome.hash)),
+        Err(e) => {
+            tracing::error!("Unable to send transfer: {}", e);
+            (false, None)
         }
-    } else {
-        (false, None)
     };
 
-    if transfer_result {
-        state.faucet_repo.add(payload.challenge.clone()).await;
+    let reveal_pk_tx_hash = match reveal_public_key {
+        Some(public_key) if transfer_result => state
+            .ledger
+            .reveal_pk(&account.account.public_key, public_key, &settings.fees)
+            .await
+            .map_err(|e| tracing::error!("Unable to reveal public key: {}", e))
+            .ok()
+            .map(|outcome| outcome.hash),
+        _ => None,
+    };
+
+    // A challenge is only given back when nothing reached the chain, a
+    // transfer that was broadcast but not applied yet may still go through.
+    match (&challenge, &api_key) {
+        (Some(challenge), _) if tx_hash.is_none() || state.dry_run => {
+            state.faucet_repo.release(challenge).await;
+        }
+        (_, Some(api_key)) if !transfer_result => {
+            state.api_key_repo.release_quota(api_key).await;
+        }
+        _ => (),
     }
 
+    tracing::info!(
+        target: "audit",
+        request_id = %request_id,
+        api_key = api_key.as_ref().map(|api_key| api_key.name.as_str()),
+        ip = %addr.ip(),
+        account = %account.account.alias,
+        target_address = %payload.transfer.target,
+        token = %payload.transfer.token,
+        amount = transfer_amount,
+        sent = transfer_result,
+        dry_run = state.dry_run,
+        tx_hash = tx_hash.as_deref(),
+        reveal_pk_tx_hash = reveal_pk_tx_hash.as_deref(),
+        "faucet transfer"
+    );
+
     let response = FaucetResponseStatusDto {
+        request_id,
         token: payload.transfer.token.clone(),
-        amount: payload.transfer.amount,
+        amount: transfer_amount,
         target: payload.transfer.target.clone(),
         sent: transfer_result,
         tx_hash,
+        fee_estimate: Some(fee_estimate),
+        dry_run: state.dry_run,
+        reveal_pk_tx_hash,
     };
 
-    Ok(Json(response))
+    Ok(response)
+}
+
+async fn faucet_balance(
+    state: &FaucetState,
+    token: &Address,
+    faucet_address: &Address,
+) -> Result<Amount, ApiError> {
+    state
+        .ledger
+        .balance(token, faucet_address)
+        .await
+        .map_err(|_| FaucetError::SdkError("Can't query faucet balance".to_string()).into())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/faucet/events/{request_id}",
+    tag = "faucet",
+    params(("request_id" = String, Path, description = "`request_id` of a transfer response")),
+    responses(
+        (status = 200, description = "Server-sent `transfer` events, ending with `applied` or `failed`", body = TransferEventDto, content_type = "text/event-stream"),
+        (status = 404, description = "Unknown request", body = ApiErrorResponse),
+    )
+)]
+pub async fn transfer_events(
+    State(state): State<FaucetState>,
+    Path(request_id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let (history, receiver) = state
+        .events
+        .subscribe(&request_id)
+        .ok_or(FaucetError::UnknownRequest)?;
+
+    Ok(event_stream(
+        state.events,
+        Followed::Request(request_id),
+        history,
+        receiver,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/faucet/events",
+    tag = "faucet",
+    params(("idempotency_key" = String, Query, description = "`idempotency-key` header of a transfer request, which may not have been sent yet")),
+    responses(
+        (status = 200, description = "Server-sent `transfer` events, ending with `applied` or `failed`", body = TransferEventDto, content_type = "text/event-stream"),
+    )
+)]
+pub async fn idempotent_transfer_events(
+    State(state): State<FaucetState>,
+    Query(query): Query<TransferEventsQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let (history, receiver) = state.events.subscribe_by_key(&query.idempotency_key);
+
+    event_stream(
+        state.events,
+        Followed::IdempotencyKey(query.idempotency_key),
+        history,
+        receiver,
+    )
+}
+
+/// What a transfer event stream follows.
+#[derive(Clone)]
+enum Followed {
+    Request(String),
+    /// Resolved to a request ID once the request is sent.
+    IdempotencyKey(String),
+}
+
+impl Followed {
+    fn request_id(&self, events: &TransferEvents) -> Option<String> {
+        match self {
+            Followed::Request(request_id) => Some(request_id.clone()),
+            Followed::IdempotencyKey(key) => events.request_id(key),
+        }
+    }
+
+    fn matches(&self, events: &TransferEvents, request_id: &str) -> bool {
+        self.request_id(events).as_deref() == Some(request_id)
+    }
+}
+
+fn event_stream(
+    events: TransferEvents,
+    followed: Followed,
+    history: Vec<TransferEventDto>,
+    receiver: Receiver<TransferEventDto>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let done = history.iter().any(|event| event.status.is_final());
+    let live = stream::unfold((receiver, done), move |(mut receiver, done)| {
+        let followed = followed.clone();
+        let events = events.clone();
+        async move {
+            if done {
+                return None;
+            }
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if followed.matches(&events, &event.request_id) => {
+                        let done = event.status.is_final();
+                        return Some((event, (receiver, done)));
+                    }
+                    Ok(_) => continue,
+                    // Skipped events may include the last one.
+                    Err(RecvError::Lagged(_)) => {
+                        match followed
+                            .request_id(&events)
+                            .and_then(|request_id| events.latest(&request_id))
+                        {
+                            Some(event) if event.status.is_final() => {
+                                return Some((event, (receiver, true)));
+                            }
+                            _ => continue,
+                        }
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }
+    });
+
+    let events = stream::iter(history).chain(live).map(|event| {
+        Ok(Event::default()
+            .event("transfer")
+            .json_data(event)
+            .unwrap_or_default())
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+/// Identifies a request, so that an idempotency key can't be reused for a
+/// different one.
+fn request_fingerprint(headers: &HeaderMap, payload: &FaucetRequestDto) -> String {
+    let api_key = headers
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(ApiKey::hash)
+        .unwrap_or_default();
+    let request = [
+        api_key.as_bytes(),
+        &serde_json::to_vec(payload).unwrap_or_default(),
+    ]
+    .concat();
+
+    match Sha256::digest(&request) {
+        Ok(digest) => HEXLOWER.encode(digest.as_ref()),
+        Err(_) => HEXLOWER.encode(&request),
+    }
+}
+
+/// Publishes the status of a submitted transfer and follows it on chain if it
+/// isn't applied yet.
+fn track_transfer(state: &FaucetState, request_id: &str, outcome: &Result<TxOutcome, NamadaError>) {
+    let outcome = match outcome {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            let mut event = TransferEventDto::new(request_id, TransferStatusDto::Failed);
+            event.error = Some(e.to_string());
+            state.events.publish(event);
+            return;
+        }
+    };
+
+    let mut event = TransferEventDto::new(request_id, TransferStatusDto::Broadcast);
+    event.tx_hash = Some(outcome.hash.clone());
+    state.events.publish(event.clone());
+
+    if outcome.applied {
+        event.status = TransferStatusDto::Applied;
+        event.height = outcome.height;
+        state.events.publish(event);
+        return;
+    }
+
+    let hash = outcome.hash.clone();
+    let ledger = state.ledger.clone();
+    let events = state.events.clone();
+    tokio::spawn(async move {
+        for _ in 0..TX_STATUS_POLLS {
+            tokio::time::sleep(TX_STATUS_INTERVAL).await;
+            match ledger.tx_status(&hash).await {
+                Ok(TxStatus::Applied) => {
+                    event.status = TransferStatusDto::Applied;
+                    events.publish(event);
+                    return;
+                }
+                Ok(TxStatus::Failed) => {
+                    event.status = TransferStatusDto::Failed;
+                    events.publish(event);
+                    return;
+                }
+                Ok(TxStatus::Pending) => (),
+                Err(e) => tracing::warn!("Unable to query transaction status: {}", e),
+            }
+        }
+
+        event.status = TransferStatusDto::Failed;
+        event.error = Some("Transaction not applied in time".to_string());
+        events.publish(event);
+    });
 }
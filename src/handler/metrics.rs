@@ -0,0 +1,41 @@
+use std::fmt::Write;
+
+use axum::extract::State;
+
+use crate::{error::api::ApiError, handler::admin::faucet_accounts, state::faucet::FaucetState};
+
+/// Prometheus text exposition of the faucet accounts. Balances come from a
+/// cache refreshed at most every 30 seconds, in-flight counts are live.
+pub async fn metrics(State(state): State<FaucetState>) -> Result<String, ApiError> {
+    let accounts = state
+        .balances
+        .get_or_refresh(|| faucet_accounts(&state))
+        .await?;
+
+    let mut output = String::new();
+    writeln!(output, "# TYPE faucet_account_balance gauge").unwrap();
+    for account in &accounts {
+        for (token, balance) in &account.balances {
+            writeln!(
+                output,
+                "faucet_account_balance{{alias=\"{}\",address=\"{}\",token=\"{}\"}} {}",
+                account.alias, account.address, token, balance
+            )
+            .unwrap();
+        }
+    }
+
+    writeln!(output, "# TYPE faucet_account_in_flight gauge").unwrap();
+    for account in state.accounts.accounts() {
+        writeln!(
+            output,
+            "faucet_account_in_flight{{alias=\"{}\",address=\"{}\"}} {}",
+            account.alias,
+            account.address,
+            account.in_flight()
+        )
+        .unwrap();
+    }
+
+    Ok(output)
+}
@@ -1 +1,4 @@
+pub mod admin;
 pub mod faucet;
+pub mod metrics;
+pub mod openapi;
@@ -0,0 +1,69 @@
+use axum::Json;
+use utoipa::{
+    openapi::security::{ApiKey, ApiKeyValue, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use crate::{
+    dto::{
+        admin::FaucetAccountDto,
+        faucet::{
+            FaucetRequestDto, FaucetResponseDto, FaucetResponseStatusDto, FaucetSettingResponse,
+            Transfer, TransferEventDto, TransferStatusDto,
+        },
+    },
+    entity::access_list::{AccessList, AccessRules},
+    handler::{admin, faucet},
+    response::api::ApiErrorResponse,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Namada faucet"),
+    paths(
+        faucet::faucet_settings,
+        faucet::request_challenge,
+        faucet::request_transfer,
+        faucet::transfer_events,
+        faucet::idempotent_transfer_events,
+        admin::get_access_list,
+        admin::update_access_list,
+        admin::get_accounts,
+    ),
+    components(schemas(
+        FaucetRequestDto,
+        Transfer,
+        FaucetResponseDto,
+        FaucetResponseStatusDto,
+        FaucetSettingResponse,
+        TransferEventDto,
+        TransferStatusDto,
+        FaucetAccountDto,
+        AccessList,
+        AccessRules,
+        ApiErrorResponse,
+    )),
+    modifiers(&AdminKey),
+    tags(
+        (name = "faucet", description = "Proof of work challenges and transfers"),
+        (name = "admin", description = "Operator endpoints, enabled with `admin_key`"),
+    )
+)]
+pub struct ApiDoc;
+
+struct AdminKey;
+
+impl Modify for AdminKey {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "admin_key",
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("x-admin-key"))),
+            );
+        }
+    }
+}
+
+pub async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
@@ -1,11 +1,15 @@
 pub mod app;
 pub mod app_state;
+pub mod cli;
+#[cfg(feature = "client")]
+pub mod client;
 pub mod config;
 
 pub mod dto;
 pub mod entity;
 pub mod error;
 pub mod handler;
+pub mod reload;
 pub mod repository;
 pub mod response;
 pub mod sdk;
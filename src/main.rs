@@ -4,21 +4,48 @@ use tokio::sync::RwLock;
 use anyhow::Context;
 use clap::Parser;
 use dotenvy::dotenv;
-use namada_faucet::{app::ApplicationServer, app_state::AppState, config::AppConfig};
+use namada_faucet::{
+    app::ApplicationServer,
+    app_state::AppState,
+    cli::{airdrop, balance, keygen, keystore, Cli, Command},
+};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenv().ok();
-    let config = Arc::new(AppConfig::parse());
-    let db = Arc::new(RwLock::new(AppState::default()));
+    let cli = Cli::parse();
 
     tracing_subscriber::fmt()
         .with_max_level(tracing::Level::INFO)
         .init();
 
-    ApplicationServer::serve(config, db)
-        .await
-        .context("could not initialize application routes")?;
+    // Commands that don't talk to the chain run without a faucet config.
+    let command = match cli.command.unwrap_or(Command::Serve) {
+        #[cfg(feature = "client")]
+        Command::Request(args) => return namada_faucet::cli::request::run(args).await,
+        #[cfg(feature = "client")]
+        Command::Solve(args) => return namada_faucet::cli::solve::run(args),
+        Command::GenAuthKey => {
+            keygen::gen_auth_key();
+            return Ok(());
+        }
+        Command::Keystore(args) => return keystore::run(args),
+        command => command,
+    };
+
+    let config = Arc::new(cli.config.load()?);
+
+    match command {
+        Command::Serve => {
+            let db = Arc::new(RwLock::new(AppState::default()));
+            ApplicationServer::serve(config, db)
+                .await
+                .context("could not initialize application routes")?
+        }
+        Command::Airdrop(args) => airdrop::run(&config, args).await?,
+        Command::Balance => balance::run(&config).await?,
+        _ => unreachable!("handled above"),
+    }
 
     Ok(())
 }
@@ -0,0 +1,156 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use notify::{RecursiveMode, Watcher};
+use tokio::sync::{mpsc, RwLock};
+
+use crate::{
+    app_state::AppState,
+    config::Config,
+    repository::{
+        access_list::{AccessListRepository, AccessListRepositoryTrait},
+        api_key::{ApiKeyRepository, ApiKeyRepositoryTrait},
+    },
+    state::faucet::FaucetSettings,
+    utils::rate_limit::RateLimiter,
+};
+
+/// Editors usually emit a burst of events when saving a file.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Reloads the configuration when the config file changes or on SIGHUP and
+/// swaps the non structural settings into the running server.
+#[derive(Clone)]
+pub struct ConfigReloader {
+    /// Configuration the server was started with, structural settings are
+    /// always compared against it.
+    config: Arc<Config>,
+    settings: Arc<RwLock<FaucetSettings>>,
+    access_list_repo: AccessListRepository,
+    api_key_repo: ApiKeyRepository,
+    rate_limiter: RateLimiter,
+}
+
+impl ConfigReloader {
+    pub fn new(
+        config: Config,
+        settings: &Arc<RwLock<FaucetSettings>>,
+        data: &Arc<RwLock<AppState>>,
+        rate_limiter: &RateLimiter,
+    ) -> Self {
+        Self {
+            config: Arc::new(config),
+            settings: settings.clone(),
+            access_list_repo: AccessListRepository::new(data),
+            api_key_repo: ApiKeyRepository::new(data),
+            rate_limiter: rate_limiter.clone(),
+        }
+    }
+
+    pub fn spawn(self) -> anyhow::Result<()> {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let watcher = match self.config.args.config.clone() {
+            Some(path) => Some(Self::watch(path, tx.clone())?),
+            None => None,
+        };
+
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            let mut hangup = signal(SignalKind::hangup())?;
+            tokio::spawn(async move {
+                while hangup.recv().await.is_some() {
+                    tracing::info!("Received SIGHUP, reloading configuration");
+                    if tx.send(()).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        tokio::spawn(async move {
+            // The watcher stops as soon as it is dropped.
+            let _watcher = watcher;
+            while rx.recv().await.is_some() {
+                tokio::time::sleep(DEBOUNCE).await;
+                while rx.try_recv().is_ok() {}
+                self.reload().await;
+            }
+        });
+
+        Ok(())
+    }
+
+    fn watch(
+        path: PathBuf,
+        tx: mpsc::UnboundedSender<()>,
+    ) -> anyhow::Result<notify::RecommendedWatcher> {
+        let file_name = path.file_name().map(|name| name.to_owned());
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                let is_config = event
+                    .paths
+                    .iter()
+                    .any(|changed| changed.file_name() == file_name.as_deref());
+                if is_config && (event.kind.is_modify() || event.kind.is_create()) {
+                    let _ = tx.send(());
+                }
+            })?;
+
+        // Watch the parent directory so files replaced on save are picked up.
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+
+        Ok(watcher)
+    }
+
+    pub async fn reload(&self) {
+        let config = match self.config.args.load() {
+            Ok(config) => config,
+            Err(e) => {
+                tracing::error!("Configuration reload rejected: {}", e);
+                return;
+            }
+        };
+
+        let ignored = self.config.structural_changes(&config);
+        if !ignored.is_empty() {
+            tracing::warn!(
+                "Configuration reload ignores settings that require a restart: {}",
+                ignored.join(", ")
+            );
+        }
+
+        *self.settings.write().await = FaucetSettings::from(&config);
+        // Without a file the list only changes through the admin API, keep
+        // those edits. A different file is a structural change and ignored.
+        if config.access_list_path.is_some()
+            && config.access_list_path == self.config.access_list_path
+        {
+            self.access_list_repo
+                .clone()
+                .set(config.access_list.clone())
+                .await;
+        }
+        self.api_key_repo
+            .clone()
+            .set_all(config.api_keys.clone())
+            .await;
+        self.rate_limiter.set_rps(config.rps.unwrap_or(u64::MAX));
+
+        tracing::info!(
+            "Configuration reloaded: difficulty {}, withdraw limit {}, {} tokens, {} API keys",
+            config.difficulty,
+            config.withdraw_limit,
+            config.tokens.len(),
+            config.api_keys.len()
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use std::{net::IpAddr, sync::Arc};
+use tokio::sync::RwLock;
+
+use async_trait::async_trait;
+
+use crate::{
+    app_state::AppState,
+    entity::access_list::{AccessDecision, AccessList},
+};
+
+#[derive(Clone)]
+pub struct AccessListRepository {
+    pub(crate) data: Arc<RwLock<AppState>>,
+}
+
+#[async_trait]
+pub trait AccessListRepositoryTrait {
+    fn new(data: &Arc<RwLock<AppState>>) -> Self;
+    async fn get(&self) -> AccessList;
+    async fn set(&mut self, access_list: AccessList);
+    async fn check(&self, address: Option<&str>, ip: Option<IpAddr>) -> AccessDecision;
+}
+
+#[async_trait]
+impl AccessListRepositoryTrait for AccessListRepository {
+    fn new(data: &Arc<RwLock<AppState>>) -> Self {
+        Self { data: data.clone() }
+    }
+
+    async fn get(&self) -> AccessList {
+        let state = self.data.read().await;
+        state.access_list.clone()
+    }
+
+    async fn set(&mut self, access_list: AccessList) {
+        let mut state = self.data.write().await;
+        state.access_list = access_list;
+    }
+
+    async fn check(&self, address: Option<&str>, ip: Option<IpAddr>) -> AccessDecision {
+        let state = self.data.read().await;
+        state.access_list.check(address, ip)
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use async_trait::async_trait;
+
+use crate::{app_state::AppState, entity::api_key::ApiKey};
+
+#[derive(Clone)]
+pub struct ApiKeyRepository {
+    pub(crate) data: Arc<RwLock<AppState>>,
+}
+
+#[async_trait]
+pub trait ApiKeyRepositoryTrait {
+    fn new(data: &Arc<RwLock<AppState>>) -> Self;
+    async fn set_all(&mut self, api_keys: Vec<ApiKey>);
+    async fn find(&self, key_hash: &str) -> Option<ApiKey>;
+    async fn reserve_quota(&mut self, api_key: &ApiKey) -> bool;
+    async fn release_quota(&mut self, api_key: &ApiKey);
+}
+
+#[async_trait]
+impl ApiKeyRepositoryTrait for ApiKeyRepository {
+    fn new(data: &Arc<RwLock<AppState>>) -> Self {
+        Self { data: data.clone() }
+    }
+
+    async fn set_all(&mut self, api_keys: Vec<ApiKey>) {
+        let mut state = self.data.write().await;
+        state.api_keys = api_keys
+            .into_iter()
+            .map(|api_key| (api_key.key_hash.clone(), api_key))
+            .collect();
+    }
+
+    async fn find(&self, key_hash: &str) -> Option<ApiKey> {
+        let state = self.data.read().await;
+        state.api_keys.get(key_hash).cloned()
+    }
+
+    async fn reserve_quota(&mut self, api_key: &ApiKey) -> bool {
+        let today = chrono::offset::Utc::now().date_naive();
+        let mut state = self.data.write().await;
+        let usage = state
+            .api_key_usage
+            .entry(api_key.key_hash.clone())
+            .or_insert((today, 0));
+
+        if usage.0 != today {
+            *usage = (today, 0);
+        }
+
+        match api_key.daily_quota {
+            Some(quota) if usage.1 >= quota => false,
+            _ => {
+                usage.1 += 1;
+                true
+            }
+        }
+    }
+
+    async fn release_quota(&mut self, api_key: &ApiKey) {
+        let mut state = self.data.write().await;
+        if let Some(usage) = state.api_key_usage.get_mut(&api_key.key_hash) {
+            usage.1 = usage.1.saturating_sub(1);
+        }
+    }
+}
@@ -13,8 +13,9 @@ pub struct FaucetRepository {
 #[async_trait]
 pub trait FaucetRepositoryTrait {
     fn new(data: &Arc<RwLock<AppState>>) -> Self;
-    async fn add(&mut self, challenge: String);
     async fn contains(&self, challenge: &str) -> bool;
+    async fn reserve(&mut self, challenge: String) -> bool;
+    async fn release(&mut self, challenge: &str);
 }
 
 #[async_trait]
@@ -23,13 +24,19 @@ impl FaucetRepositoryTrait for FaucetRepository {
         Self { data: data.clone() }
     }
 
-    async fn add(&mut self, challenge: String) {
+    async fn contains(&self, challenge: &str) -> bool {
+        let state = self.data.read().await;
+        state.contains(&challenge.to_string())
+    }
+
+    /// Marks `challenge` as used, unless it already is.
+    async fn reserve(&mut self, challenge: String) -> bool {
         let mut state = self.data.write().await;
         state.add(challenge)
     }
 
-    async fn contains(&self, challenge: &str) -> bool {
-        let state = self.data.read().await;
-        state.contains(&challenge.to_string())
+    async fn release(&mut self, challenge: &str) {
+        let mut state = self.data.write().await;
+        state.remove(&challenge.to_string())
     }
 }
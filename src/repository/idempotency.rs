@@ -0,0 +1,74 @@
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+
+use crate::{
+    app_state::AppState,
+    dto::faucet::FaucetResponseStatusDto,
+    entity::idempotency::{IdempotencyRecord, IdempotencyStatus},
+};
+
+/// How long responses are kept for retries.
+const RECORD_TTL_HOURS: i64 = 24;
+/// Requests still in flight after this long are considered abandoned.
+const IN_FLIGHT_TTL_MINUTES: i64 = 5;
+
+#[derive(Clone)]
+pub struct IdempotencyRepository {
+    pub(crate) data: Arc<RwLock<AppState>>,
+}
+
+#[async_trait]
+pub trait IdempotencyRepositoryTrait {
+    fn new(data: &Arc<RwLock<AppState>>) -> Self;
+    async fn begin(&mut self, key: &str, fingerprint: &str) -> IdempotencyStatus;
+    async fn complete(&mut self, key: &str, response: FaucetResponseStatusDto);
+    async fn abort(&mut self, key: &str);
+}
+
+#[async_trait]
+impl IdempotencyRepositoryTrait for IdempotencyRepository {
+    fn new(data: &Arc<RwLock<AppState>>) -> Self {
+        Self { data: data.clone() }
+    }
+
+    async fn begin(&mut self, key: &str, fingerprint: &str) -> IdempotencyStatus {
+        let mut state = self.data.write().await;
+        let now = Utc::now();
+        state
+            .idempotency_keys
+            .retain(|_, record| match record.response {
+                Some(_) => now - record.created_at < Duration::hours(RECORD_TTL_HOURS),
+                None => now - record.created_at < Duration::minutes(IN_FLIGHT_TTL_MINUTES),
+            });
+
+        match state.idempotency_keys.get(key) {
+            Some(record) if record.fingerprint != fingerprint => IdempotencyStatus::Mismatch,
+            Some(record) => match &record.response {
+                Some(response) => IdempotencyStatus::Done(response.clone()),
+                None => IdempotencyStatus::InFlight,
+            },
+            None => {
+                state.idempotency_keys.insert(
+                    key.to_string(),
+                    IdempotencyRecord::new(fingerprint.to_string()),
+                );
+                IdempotencyStatus::New
+            }
+        }
+    }
+
+    async fn complete(&mut self, key: &str, response: FaucetResponseStatusDto) {
+        let mut state = self.data.write().await;
+        if let Some(record) = state.idempotency_keys.get_mut(key) {
+            record.response = Some(response);
+        }
+    }
+
+    async fn abort(&mut self, key: &str) {
+        let mut state = self.data.write().await;
+        state.idempotency_keys.remove(key);
+    }
+}
@@ -1 +1,4 @@
+pub mod access_list;
+pub mod api_key;
 pub mod faucet;
+pub mod idempotency;
@@ -14,4 +14,10 @@ pub enum NamadaError {
     ConversionInvalid(String),
     #[error("Can't find secret key")]
     InvalidSecretKey,
+    #[error("Query failed: {0}")]
+    QueryInvalid(String),
+    #[error("Invalid fee settings: {0}")]
+    FeeInvalid(String),
+    #[error("Verification failed: {0}")]
+    VerificationInvalid(String),
 }
@@ -0,0 +1,134 @@
+use std::{fs, path::Path, path::PathBuf, str::FromStr};
+
+use anyhow::{anyhow, Context};
+use data_encoding::HEXLOWER;
+use namada_sdk::{key::common::SecretKey, wallet::fs::FsWalletUtils};
+use orion::{aead, kdf};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+const KDF_ITERATIONS: u32 = 3;
+const KDF_MEMORY: u32 = 1 << 16;
+const KDF_KEY_LENGTH: u32 = 32;
+
+/// Where the faucet signing key is loaded from.
+#[derive(Clone, Debug)]
+pub enum KeySource {
+    Raw(Zeroizing<String>),
+    File(PathBuf),
+    Wallet {
+        dir: PathBuf,
+        alias: String,
+        password_file: Option<PathBuf>,
+    },
+    Keystore {
+        path: PathBuf,
+        password_file: PathBuf,
+    },
+}
+
+/// Secret key encrypted with a key derived from a password with Argon2i.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Keystore {
+    pub salt: String,
+    pub iterations: u32,
+    pub memory: u32,
+    pub ciphertext: String,
+}
+
+impl KeySource {
+    pub fn load(&self) -> anyhow::Result<SecretKey> {
+        match self {
+            KeySource::Raw(sk) => parse_secret_key(sk),
+            KeySource::File(path) => {
+                let sk = read_secret(path)?;
+                parse_secret_key(&sk)
+            }
+            KeySource::Wallet {
+                dir,
+                alias,
+                password_file,
+            } => {
+                let password = password_file.as_deref().map(read_secret).transpose()?;
+                let mut wallet = FsWalletUtils::new(dir.clone());
+                wallet
+                    .load()
+                    .map_err(|e| anyhow!("can't load wallet {}: {}", dir.display(), e))?;
+                wallet
+                    .find_secret_key(alias, password)
+                    .map_err(|e| anyhow!("can't find key {} in wallet: {}", alias, e))
+            }
+            KeySource::Keystore {
+                path,
+                password_file,
+            } => {
+                let password = read_secret(password_file)?;
+                let content = fs::read_to_string(path)
+                    .with_context(|| format!("can't read keystore {}", path.display()))?;
+                let keystore: Keystore = serde_json::from_str(&content)
+                    .with_context(|| format!("invalid keystore {}", path.display()))?;
+                let sk = keystore.decrypt(&password)?;
+                parse_secret_key(&sk)
+            }
+        }
+    }
+}
+
+impl Keystore {
+    pub fn encrypt(sk: &str, password: &str) -> anyhow::Result<Self> {
+        let salt = kdf::Salt::default();
+        let key = derive_key(password, &salt, KDF_ITERATIONS, KDF_MEMORY)?;
+        let ciphertext =
+            aead::seal(&key, sk.as_bytes()).map_err(|_| anyhow!("can't encrypt secret key"))?;
+
+        Ok(Self {
+            salt: HEXLOWER.encode(salt.as_ref()),
+            iterations: KDF_ITERATIONS,
+            memory: KDF_MEMORY,
+            ciphertext: HEXLOWER.encode(&ciphertext),
+        })
+    }
+
+    pub fn decrypt(&self, password: &str) -> anyhow::Result<Zeroizing<String>> {
+        let salt = HEXLOWER
+            .decode(self.salt.as_bytes())
+            .map_err(|_| anyhow!("invalid keystore salt"))?;
+        let salt = kdf::Salt::from_slice(&salt).map_err(|_| anyhow!("invalid keystore salt"))?;
+        let ciphertext = HEXLOWER
+            .decode(self.ciphertext.as_bytes())
+            .map_err(|_| anyhow!("invalid keystore ciphertext"))?;
+
+        let key = derive_key(password, &salt, self.iterations, self.memory)?;
+        let plaintext = Zeroizing::new(
+            aead::open(&key, &ciphertext).map_err(|_| anyhow!("wrong keystore password"))?,
+        );
+
+        std::str::from_utf8(&plaintext)
+            .map(|sk| Zeroizing::new(sk.to_string()))
+            .map_err(|_| anyhow!("invalid keystore content"))
+    }
+}
+
+fn derive_key(
+    password: &str,
+    salt: &kdf::Salt,
+    iterations: u32,
+    memory: u32,
+) -> anyhow::Result<kdf::SecretKey> {
+    let password =
+        kdf::Password::from_slice(password.as_bytes()).map_err(|_| anyhow!("invalid password"))?;
+    kdf::derive_key(&password, salt, iterations, memory, KDF_KEY_LENGTH)
+        .map_err(|_| anyhow!("can't derive keystore key"))
+}
+
+/// Reads a key or password file, surrounding whitespace is ignored.
+pub fn read_secret(path: &Path) -> anyhow::Result<Zeroizing<String>> {
+    let content = Zeroizing::new(
+        fs::read_to_string(path).with_context(|| format!("can't read {}", path.display()))?,
+    );
+    Ok(Zeroizing::new(content.trim().to_string()))
+}
+
+fn parse_secret_key(sk: &str) -> anyhow::Result<SecretKey> {
+    SecretKey::from_str(sk).map_err(|_| anyhow!("not a valid secret key"))
+}
@@ -0,0 +1,73 @@
+use async_trait::async_trait;
+use namada_sdk::{address::Address, key::common::PublicKey, token::Amount};
+
+use crate::{config::FeeSettings, sdk::error::NamadaError};
+
+/// A single transparent transfer, amounts are in base units.
+#[derive(Clone, Debug)]
+pub struct Transfer {
+    pub source: Address,
+    pub target: Address,
+    pub token: Address,
+    pub amount: Amount,
+}
+
+/// Fee a transaction is expected to pay with the current fee settings.
+#[derive(Clone, Debug)]
+pub struct FeeEstimate {
+    pub token: Address,
+    pub amount: Amount,
+}
+
+/// Result of a transaction submitted to the ledger. A transaction that was
+/// only signed, in dry run mode, is never applied.
+#[derive(Clone, Debug)]
+pub struct TxOutcome {
+    pub hash: String,
+    pub applied: bool,
+    pub height: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TxStatus {
+    Pending,
+    Applied,
+    Failed,
+}
+
+/// Everything the faucet needs from the chain. Handlers only go through this
+/// trait so that they can run against an in-memory ledger.
+#[async_trait]
+pub trait LedgerClient: Send + Sync {
+    async fn native_token(&self) -> Result<Address, NamadaError>;
+
+    async fn balance(&self, token: &Address, owner: &Address) -> Result<Amount, NamadaError>;
+
+    /// Formats `amount` with the denomination of `token`.
+    async fn denominate(&self, token: &Address, amount: Amount) -> String;
+
+    async fn is_public_key_revealed(&self, owner: &Address) -> Result<bool, NamadaError>;
+
+    async fn estimate_fee(&self, fees: &FeeSettings) -> Result<FeeEstimate, NamadaError>;
+
+    /// Builds and signs `transfer` with `signer`, which also pays the fee, and
+    /// submits it unless `dry_run` is set.
+    async fn transfer(
+        &self,
+        signer: &PublicKey,
+        transfer: Transfer,
+        memo: &str,
+        fees: &FeeSettings,
+        dry_run: bool,
+    ) -> Result<TxOutcome, NamadaError>;
+
+    /// Reveals `public_key` on behalf of its owner, `signer` pays the fee.
+    async fn reveal_pk(
+        &self,
+        signer: &PublicKey,
+        public_key: PublicKey,
+        fees: &FeeSettings,
+    ) -> Result<TxOutcome, NamadaError>;
+
+    async fn tx_status(&self, hash: &str) -> Result<TxStatus, NamadaError>;
+}
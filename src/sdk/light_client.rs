@@ -0,0 +1,612 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Context};
+use ics23::{CommitmentProof, HostFunctionsManager};
+use namada_sdk::{
+    account::pk_key,
+    address::Address,
+    borsh::BorshDeserialize,
+    chain::BlockHeight,
+    rpc,
+    state::{ics23_specs, Sha256Hasher},
+    storage::Key,
+    token::{self, Amount},
+    tx::Tx,
+};
+use prost::Message;
+use tendermint::{
+    abci::types::ExecTxResult,
+    block::Height,
+    crypto::{default::Sha256, Sha256 as _},
+    merkle::{self, proof::ProofOps},
+    Hash,
+};
+use tendermint_light_client::{
+    builder::LightClientBuilder,
+    instance::Instance,
+    light_client::Options,
+    store::memory::MemoryStore,
+    types::{LightBlock, TrustThreshold},
+};
+use tendermint_proto::v0_38::abci::ExecTxResult as RawExecTxResult;
+use tendermint_rpc::{Client, HttpClient, Url};
+
+use crate::config::TrustRoot;
+
+const RPC_TIMEOUT: Duration = Duration::from_secs(10);
+/// Balances are checked against a verified header at most this old.
+const LATEST_HEADER_TTL: Duration = Duration::from_secs(10);
+/// How long to wait for the block after a transaction's block, whose header
+/// commits to the results.
+const NEXT_HEADER_ATTEMPTS: u32 = 15;
+const NEXT_HEADER_DELAY: Duration = Duration::from_secs(1);
+
+/// Outcome of checking a transaction against verified headers. Errors mean
+/// the node's answers contradict the verified chain.
+#[derive(Debug)]
+pub enum TxVerification {
+    Verified,
+    /// The blocks needed couldn't be fetched and verified, nothing is known
+    /// either way so the transaction can't be counted as applied yet.
+    Unavailable(anyhow::Error),
+}
+
+/// Tracks headers from a trusted block and checks what the RPC node answers
+/// against them, so that a lying or lagging node can't make the faucet
+/// believe in balances or transaction results that aren't on chain.
+#[derive(Clone)]
+pub struct LightClientVerifier {
+    client: HttpClient,
+    instance: Arc<Mutex<Instance>>,
+    latest: Arc<Mutex<Option<(Instant, LightBlock)>>>,
+}
+
+impl LightClientVerifier {
+    pub async fn new(rpc: &Url, trust_root: &TrustRoot) -> anyhow::Result<Self> {
+        let client = HttpClient::new(rpc.clone()).context("unable to create RPC client")?;
+        let peer_id = client
+            .status()
+            .await
+            .context("unable to query node status")?
+            .node_info
+            .id;
+
+        let options = Options {
+            trust_threshold: TrustThreshold::default(),
+            trusting_period: trust_root.trusting_period,
+            clock_drift: Duration::from_secs(60),
+        };
+        let (light_client_rpc, height, hash) = (client.clone(), trust_root.height, trust_root.hash);
+
+        // The light client does blocking IO, it must stay off the runtime.
+        let instance = tokio::task::spawn_blocking(move || {
+            LightClientBuilder::prod(
+                peer_id,
+                light_client_rpc,
+                Box::new(MemoryStore::new()),
+                options,
+                Some(RPC_TIMEOUT),
+            )
+            .trust_primary_at(height, hash)
+            .map(|builder| builder.build())
+        })
+        .await?
+        .context("unable to initialize the light client from the trust root")?;
+
+        Ok(Self {
+            client,
+            instance: Arc::new(Mutex::new(instance)),
+            latest: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Verifies the header at `height`, or the latest one, from the trusted
+    /// state.
+    pub async fn verify_header(&self, height: Option<Height>) -> anyhow::Result<LightBlock> {
+        let instance = self.instance.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let mut instance = instance.lock().unwrap();
+            let Instance {
+                light_client,
+                state,
+            } = &mut *instance;
+            match height {
+                Some(height) => light_client.verify_to_target(height, state),
+                None => light_client.verify_to_highest(state),
+            }
+        })
+        .await?
+        .map_err(|e| anyhow!("header verification failed: {}", e))
+    }
+
+    /// The latest verified header, reused for [`LATEST_HEADER_TTL`] so that
+    /// every balance query doesn't verify up to the tip again.
+    async fn latest_header(&self) -> anyhow::Result<LightBlock> {
+        if let Some((verified_at, block)) = self.latest.lock().unwrap().as_ref() {
+            if verified_at.elapsed() < LATEST_HEADER_TTL {
+                return Ok(block.clone());
+            }
+        }
+
+        let block = self.verify_header(None).await?;
+        *self.latest.lock().unwrap() = Some((Instant::now(), block.clone()));
+
+        Ok(block)
+    }
+
+    /// Verifies the header at `height`, retrying while the block isn't
+    /// committed yet.
+    async fn wait_for_header(&self, height: Height) -> anyhow::Result<LightBlock> {
+        let mut attempt = 1;
+        loop {
+            match self.verify_header(Some(height)).await {
+                Ok(block) => return Ok(block),
+                Err(_) if attempt < NEXT_HEADER_ATTEMPTS => {
+                    tokio::time::sleep(NEXT_HEADER_DELAY).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Reads `key` with a proof and checks it against the app hash of the
+    /// latest verified header. `None` when the key is proven to be absent.
+    async fn verified_value(&self, key: &Key) -> anyhow::Result<Option<Vec<u8>>> {
+        // The app hash of a block is committed in the header of the next one.
+        let latest = self.latest_header().await?;
+        let height = latest.height().value() - 1;
+        let app_hash = latest.signed_header.header.app_hash.as_bytes().to_vec();
+
+        let (value, proof) =
+            rpc::query_storage_value_bytes(&self.client, key, Some(BlockHeight(height)), true)
+                .await
+                .with_context(|| format!("unable to query {}", key))?;
+        let proof = proof.ok_or_else(|| anyhow!("the node didn't return a proof"))?;
+
+        verify_storage_proof(
+            &proof,
+            key.to_string().as_bytes(),
+            value.as_deref(),
+            &app_hash,
+        )?;
+
+        Ok(value)
+    }
+
+    /// The balance of `owner`, verified against the latest header.
+    pub async fn verified_balance(
+        &self,
+        token: &Address,
+        owner: &Address,
+    ) -> anyhow::Result<Amount> {
+        let key = token::storage_key::balance_key(token, owner);
+        match self.verified_value(&key).await? {
+            Some(bytes) => Amount::try_from_slice(&bytes).context("invalid balance encoding"),
+            None => Ok(Amount::zero()),
+        }
+    }
+
+    /// Whether the public key of `owner` is revealed, verified against the
+    /// latest header.
+    pub async fn verified_public_key_revealed(&self, owner: &Address) -> anyhow::Result<bool> {
+        Ok(self.verified_value(&pk_key(owner, 0)).await?.is_some())
+    }
+
+    /// Checks that the transaction with header hash `tx_hash` was included
+    /// at `height` in a verified block and that its result was successful.
+    /// The results are committed in the next block, which is waited for.
+    pub async fn verify_tx_applied(
+        &self,
+        tx_hash: &str,
+        height: u64,
+    ) -> anyhow::Result<TxVerification> {
+        let height = Height::try_from(height)?;
+        let fetched = async {
+            let header = self.verify_header(Some(height)).await?.signed_header.header;
+            let next_header = self
+                .wait_for_header(height.increment())
+                .await?
+                .signed_header
+                .header;
+            let block = self.client.block(height).await?.block;
+            let results = self
+                .client
+                .block_results(height)
+                .await?
+                .txs_results
+                .unwrap_or_default();
+            anyhow::Ok((header, next_header, block, results))
+        };
+        let (header, next_header, block, results) = match fetched.await {
+            Ok(fetched) => fetched,
+            Err(e) => return Ok(TxVerification::Unavailable(e)),
+        };
+
+        check_tx_applied(
+            tx_hash,
+            height,
+            header.data_hash,
+            next_header.last_results_hash,
+            &block.data,
+            &results,
+        )?;
+
+        Ok(TxVerification::Verified)
+    }
+}
+
+/// Checks the transactions and results of the block at `height` against the
+/// hashes committed in the verified headers, then that the transaction with
+/// header hash `tx_hash` is in the block and succeeded.
+fn check_tx_applied(
+    tx_hash: &str,
+    height: Height,
+    data_hash: Option<Hash>,
+    last_results_hash: Option<Hash>,
+    txs: &[Vec<u8>],
+    results: &[ExecTxResult],
+) -> anyhow::Result<()> {
+    if data_hash != Some(Hash::Sha256(txs_hash(txs))) {
+        bail!(
+            "block {} transactions don't match the verified header",
+            height
+        );
+    }
+
+    let index = txs
+        .iter()
+        .position(|bytes| {
+            Tx::try_from(bytes.as_slice())
+                .is_ok_and(|tx| tx.header_hash().to_string().eq_ignore_ascii_case(tx_hash))
+        })
+        .ok_or_else(|| anyhow!("transaction {} isn't in block {}", tx_hash, height))?;
+
+    if last_results_hash != Some(Hash::Sha256(results_hash(results))) {
+        bail!("block {} results don't match the verified header", height);
+    }
+
+    match results.get(index) {
+        Some(result) if result.code.is_ok() => Ok(()),
+        Some(result) => bail!(
+            "transaction {} failed with code {}",
+            tx_hash,
+            result.code.value()
+        ),
+        None => bail!("missing result for transaction {}", tx_hash),
+    }
+}
+
+/// Merkle root of the transactions of a block, its header's `data_hash`.
+fn txs_hash(txs: &[Vec<u8>]) -> [u8; 32] {
+    let leaves = txs
+        .iter()
+        .map(|tx| Sha256::digest(tx).to_vec())
+        .collect::<Vec<_>>();
+    merkle::simple_hash_from_byte_vectors::<Sha256>(&leaves)
+}
+
+/// Merkle root of the results of a block, committed in the next header's
+/// `last_results_hash`.
+fn results_hash(results: &[ExecTxResult]) -> [u8; 32] {
+    let leaves = results
+        .iter()
+        .map(|result| {
+            // Only the deterministic fields are part of the results hash.
+            RawExecTxResult {
+                code: result.code.value(),
+                data: result.data.clone(),
+                gas_wanted: result.gas_wanted,
+                gas_used: result.gas_used,
+                ..Default::default()
+            }
+            .encode_to_vec()
+        })
+        .collect::<Vec<_>>();
+    merkle::simple_hash_from_byte_vectors::<Sha256>(&leaves)
+}
+
+/// Verifies the chain of proofs from the storage subspace tree up to the
+/// app hash. `value` is `None` when the key is proven to be absent.
+fn verify_storage_proof(
+    proof: &ProofOps,
+    key: &[u8],
+    value: Option<&[u8]>,
+    app_hash: &[u8],
+) -> anyhow::Result<()> {
+    let specs = ics23_specs::proof_specs::<Sha256Hasher>();
+    if proof.ops.len() != specs.len() {
+        bail!("unexpected number of proof operations");
+    }
+
+    if proof.ops.first().map(|op| op.key.as_slice()) != Some(key) {
+        bail!("storage proof is for another key");
+    }
+
+    let mut value = value.map(<[u8]>::to_vec);
+    for (index, (op, spec)) in proof.ops.iter().zip(specs.iter()).enumerate() {
+        let commitment =
+            CommitmentProof::decode(op.data.as_slice()).context("invalid proof encoding")?;
+        let root = proof_root(&commitment)?;
+
+        let is_valid = match &value {
+            Some(value) => ics23::verify_membership::<HostFunctionsManager>(
+                &commitment,
+                spec,
+                &root,
+                &op.key,
+                value,
+            ),
+            None if index == 0 => ics23::verify_non_membership::<HostFunctionsManager>(
+                &commitment,
+                spec,
+                &root,
+                &op.key,
+            ),
+            None => false,
+        };
+        if !is_valid {
+            bail!("invalid storage proof");
+        }
+
+        // Each sub-tree root is the value proven in the tree above it.
+        value = Some(root);
+    }
+
+    if value.as_deref() != Some(app_hash) {
+        bail!("storage proof doesn't match the verified app hash");
+    }
+
+    Ok(())
+}
+
+fn proof_root(proof: &CommitmentProof) -> anyhow::Result<Vec<u8>> {
+    use ics23::commitment_proof::Proof;
+
+    let existence = match &proof.proof {
+        Some(Proof::Exist(existence)) => existence,
+        Some(Proof::Nonexist(nonexistence)) => nonexistence
+            .left
+            .as_ref()
+            .or(nonexistence.right.as_ref())
+            .ok_or_else(|| anyhow!("empty non existence proof"))?,
+        _ => bail!("unsupported proof type"),
+    };
+
+    ics23::calculate_existence_root::<HostFunctionsManager>(existence)
+        .map_err(|e| anyhow!("invalid proof: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_sdk::{
+        key::{common, ed25519, RefTo, SigScheme},
+        state::{MembershipProof, MerkleTree},
+    };
+    use rand::rngs::OsRng;
+    use tendermint::abci::Code;
+
+    use super::*;
+
+    fn address() -> Address {
+        let sk = common::SecretKey::Ed25519(ed25519::SigScheme::generate(&mut OsRng));
+        Address::from(&sk.ref_to())
+    }
+
+    fn balance_key() -> Key {
+        token::storage_key::balance_key(&address(), &address())
+    }
+
+    /// A proof of `key` holding `value` and the app hash it leads to.
+    fn proven(key: &Key, value: &[u8]) -> (ProofOps, Vec<u8>) {
+        let mut tree = MerkleTree::<Sha256Hasher>::default();
+        tree.update(key, value).unwrap();
+        let MembershipProof::ICS23(proof) = tree
+            .get_sub_tree_existence_proof(std::slice::from_ref(key), vec![value])
+            .unwrap();
+        let proof = tree.get_sub_tree_proof(key, proof).unwrap().into();
+
+        (proof, tree.root().0.to_vec())
+    }
+
+    #[test]
+    fn storage_proofs_are_checked_up_to_the_app_hash() {
+        let key = balance_key();
+        let (proof, app_hash) = proven(&key, b"value");
+
+        verify_storage_proof(
+            &proof,
+            key.to_string().as_bytes(),
+            Some(b"value".as_slice()),
+            &app_hash,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn storage_proofs_of_another_value_are_rejected() {
+        let key = balance_key();
+        let (proof, app_hash) = proven(&key, b"value");
+
+        let error = verify_storage_proof(
+            &proof,
+            key.to_string().as_bytes(),
+            Some(b"other".as_slice()),
+            &app_hash,
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "invalid storage proof");
+    }
+
+    #[test]
+    fn storage_proofs_of_another_key_are_rejected() {
+        let key = balance_key();
+        let (proof, app_hash) = proven(&key, b"value");
+
+        let error =
+            verify_storage_proof(&proof, b"another key", Some(b"value".as_slice()), &app_hash)
+                .unwrap_err();
+        assert_eq!(error.to_string(), "storage proof is for another key");
+    }
+
+    #[test]
+    fn storage_proofs_for_another_app_hash_are_rejected() {
+        let key = balance_key();
+        let (proof, _) = proven(&key, b"value");
+
+        let error = verify_storage_proof(
+            &proof,
+            key.to_string().as_bytes(),
+            Some(b"value".as_slice()),
+            &[0; 32],
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "storage proof doesn't match the verified app hash"
+        );
+    }
+
+    #[test]
+    fn malformed_storage_proofs_are_rejected() {
+        let key = balance_key();
+        let (mut proof, app_hash) = proven(&key, b"value");
+        proof.ops[0].data = vec![1, 2, 3];
+
+        assert!(verify_storage_proof(
+            &proof,
+            key.to_string().as_bytes(),
+            Some(b"value".as_slice()),
+            &app_hash
+        )
+        .is_err());
+    }
+
+    /// A block of three transactions whose second one failed.
+    fn block() -> (Vec<Vec<u8>>, Vec<ExecTxResult>, Vec<String>) {
+        let txs = (0..3)
+            .map(|index| {
+                let mut tx = Tx::default();
+                tx.add_memo(format!("tx {}", index).as_bytes());
+                tx
+            })
+            .collect::<Vec<_>>();
+        let results = [Code::Ok, Code::from(1), Code::Ok]
+            .into_iter()
+            .map(|code| ExecTxResult {
+                code,
+                ..Default::default()
+            })
+            .collect();
+
+        (
+            txs.iter().map(Tx::to_bytes).collect(),
+            results,
+            txs.iter().map(|tx| tx.header_hash().to_string()).collect(),
+        )
+    }
+
+    fn check(
+        tx_hash: &str,
+        data_hash: [u8; 32],
+        last_results_hash: [u8; 32],
+        txs: &[Vec<u8>],
+        results: &[ExecTxResult],
+    ) -> anyhow::Result<()> {
+        check_tx_applied(
+            tx_hash,
+            Height::from(10_u32),
+            Some(Hash::Sha256(data_hash)),
+            Some(Hash::Sha256(last_results_hash)),
+            txs,
+            results,
+        )
+    }
+
+    #[test]
+    fn applied_transactions_are_verified() {
+        let (txs, results, hashes) = block();
+
+        check(
+            &hashes[0],
+            txs_hash(&txs),
+            results_hash(&results),
+            &txs,
+            &results,
+        )
+        .unwrap();
+        check(
+            &hashes[2].to_lowercase(),
+            txs_hash(&txs),
+            results_hash(&results),
+            &txs,
+            &results,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn failed_transactions_are_rejected() {
+        let (txs, results, hashes) = block();
+
+        let error = check(
+            &hashes[1],
+            txs_hash(&txs),
+            results_hash(&results),
+            &txs,
+            &results,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("failed with code 1"));
+    }
+
+    #[test]
+    fn transactions_missing_from_the_block_are_rejected() {
+        let (txs, results, _) = block();
+        let mut tx = Tx::default();
+        tx.add_memo(b"elsewhere");
+
+        let error = check(
+            &tx.header_hash().to_string(),
+            txs_hash(&txs),
+            results_hash(&results),
+            &txs,
+            &results,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("isn't in block 10"));
+    }
+
+    #[test]
+    fn blocks_not_matching_the_verified_headers_are_rejected() {
+        let (txs, results, hashes) = block();
+
+        // The node hides a transaction from the block.
+        let error = check(
+            &hashes[0],
+            txs_hash(&txs),
+            results_hash(&results),
+            &txs[..2],
+            &results,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("transactions don't match"));
+
+        // The node reports the failed transaction as successful.
+        let mut forged = results.clone();
+        forged[1].code = Code::Ok;
+        let error = check(
+            &hashes[1],
+            txs_hash(&txs),
+            results_hash(&results),
+            &txs,
+            &forged,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("results don't match"));
+    }
+}
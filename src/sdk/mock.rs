@@ -0,0 +1,241 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+use namada_sdk::{address::Address, key::common::PublicKey, token::Amount};
+
+use crate::{
+    config::FeeSettings,
+    sdk::{
+        error::NamadaError,
+        ledger::{FeeEstimate, LedgerClient, Transfer, TxOutcome, TxStatus},
+    },
+};
+
+/// In-memory [`LedgerClient`]. Transfers move balances right away and every
+/// transaction pays `fee` in the native token.
+pub struct MockLedger {
+    native_token: Address,
+    fee: Amount,
+    state: Mutex<MockState>,
+}
+
+#[derive(Default)]
+struct MockState {
+    balances: HashMap<(Address, Address), Amount>,
+    revealed: HashSet<Address>,
+    txs: HashMap<String, TxStatus>,
+    memos: HashMap<String, String>,
+    held: Option<HashMap<String, HeldTransfer>>,
+    nonce: u64,
+}
+
+/// A transfer broadcast while held, applied once settled.
+struct HeldTransfer {
+    fee: FeeEstimate,
+    payer: Address,
+    transfer: Transfer,
+}
+
+impl MockLedger {
+    pub fn new(native_token: Address, fee: Amount) -> Self {
+        Self {
+            native_token,
+            fee,
+            state: Mutex::new(MockState::default()),
+        }
+    }
+
+    pub fn set_balance(&self, token: &Address, owner: &Address, amount: Amount) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .balances
+            .insert((token.clone(), owner.clone()), amount);
+    }
+
+    /// Hashes of every transaction submitted so far.
+    pub fn transactions(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        state.txs.keys().cloned().collect()
+    }
+
+    /// Memo attached to the transfer with `hash`.
+    pub fn memo(&self, hash: &str) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        state.memos.get(hash).cloned()
+    }
+
+    /// While held, transfers are broadcast but stay pending until
+    /// [`MockLedger::settle`] is called. Nothing is debited until then.
+    pub fn hold(&self, hold: bool) {
+        let mut state = self.state.lock().unwrap();
+        if hold {
+            state.held.get_or_insert_with(HashMap::new);
+        } else {
+            state.held = None;
+        }
+    }
+
+    /// Moves a held transaction out of the mempool, applying it if `status`
+    /// is [`TxStatus::Applied`].
+    pub fn settle(&self, hash: &str, status: TxStatus) -> Result<(), NamadaError> {
+        let mut state = self.state.lock().unwrap();
+        let held = state
+            .held
+            .as_mut()
+            .and_then(|held| held.remove(hash))
+            .ok_or_else(|| NamadaError::TxBroadcastingInvalid(format!("{} isn't held", hash)))?;
+        if status == TxStatus::Applied {
+            Self::apply(&mut state, held)?;
+        }
+        state.txs.insert(hash.to_string(), status);
+        Ok(())
+    }
+
+    fn apply(state: &mut MockState, held: HeldTransfer) -> Result<(), NamadaError> {
+        let HeldTransfer {
+            fee,
+            payer,
+            transfer,
+        } = held;
+        Self::debit(state, &fee.token, &payer, fee.amount)?;
+        Self::debit(state, &transfer.token, &transfer.source, transfer.amount)?;
+        let balance = state
+            .balances
+            .entry((transfer.token, transfer.target))
+            .or_default();
+        *balance = balance
+            .checked_add(transfer.amount)
+            .ok_or_else(|| NamadaError::TxBroadcastingInvalid("balance overflow".to_string()))?;
+        Ok(())
+    }
+
+    fn debit(
+        state: &mut MockState,
+        token: &Address,
+        owner: &Address,
+        amount: Amount,
+    ) -> Result<(), NamadaError> {
+        let balance = state
+            .balances
+            .entry((token.clone(), owner.clone()))
+            .or_default();
+        *balance = balance.checked_sub(amount).ok_or_else(|| {
+            NamadaError::TxBroadcastingInvalid(format!("insufficient balance for {}", owner))
+        })?;
+        Ok(())
+    }
+
+    fn next_hash(state: &mut MockState) -> String {
+        state.nonce += 1;
+        format!("{:064X}", state.nonce)
+    }
+}
+
+#[async_trait]
+impl LedgerClient for MockLedger {
+    async fn native_token(&self) -> Result<Address, NamadaError> {
+        Ok(self.native_token.clone())
+    }
+
+    async fn balance(&self, token: &Address, owner: &Address) -> Result<Amount, NamadaError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .balances
+            .get(&(token.clone(), owner.clone()))
+            .copied()
+            .unwrap_or_default())
+    }
+
+    async fn denominate(&self, _token: &Address, amount: Amount) -> String {
+        amount.to_string_native()
+    }
+
+    async fn is_public_key_revealed(&self, owner: &Address) -> Result<bool, NamadaError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.revealed.contains(owner))
+    }
+
+    async fn estimate_fee(&self, fees: &FeeSettings) -> Result<FeeEstimate, NamadaError> {
+        Ok(FeeEstimate {
+            token: fees
+                .token
+                .clone()
+                .unwrap_or_else(|| self.native_token.clone()),
+            amount: self.fee,
+        })
+    }
+
+    async fn transfer(
+        &self,
+        signer: &PublicKey,
+        transfer: Transfer,
+        memo: &str,
+        fees: &FeeSettings,
+        dry_run: bool,
+    ) -> Result<TxOutcome, NamadaError> {
+        let fee = self.estimate_fee(fees).await?;
+        let mut state = self.state.lock().unwrap();
+        let hash = Self::next_hash(&mut state);
+        state.memos.insert(hash.clone(), memo.to_string());
+        if dry_run {
+            return Ok(TxOutcome {
+                hash,
+                applied: false,
+                height: None,
+            });
+        }
+
+        let held = HeldTransfer {
+            fee,
+            payer: Address::from(signer),
+            transfer,
+        };
+        let status = match state.held.as_mut() {
+            Some(pending) => {
+                pending.insert(hash.clone(), held);
+                TxStatus::Pending
+            }
+            None => {
+                Self::apply(&mut state, held)?;
+                TxStatus::Applied
+            }
+        };
+        state.txs.insert(hash.clone(), status);
+
+        let applied = status == TxStatus::Applied;
+        Ok(TxOutcome {
+            hash,
+            applied,
+            height: applied.then_some(state.nonce),
+        })
+    }
+
+    async fn reveal_pk(
+        &self,
+        signer: &PublicKey,
+        public_key: PublicKey,
+        fees: &FeeSettings,
+    ) -> Result<TxOutcome, NamadaError> {
+        let fee = self.estimate_fee(fees).await?;
+        let mut state = self.state.lock().unwrap();
+        Self::debit(&mut state, &fee.token, &Address::from(signer), fee.amount)?;
+
+        let hash = Self::next_hash(&mut state);
+        state.revealed.insert(Address::from(&public_key));
+        state.txs.insert(hash.clone(), TxStatus::Applied);
+
+        Ok(TxOutcome {
+            hash,
+            applied: true,
+            height: Some(state.nonce),
+        })
+    }
+
+    async fn tx_status(&self, hash: &str) -> Result<TxStatus, NamadaError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.txs.get(hash).copied().unwrap_or(TxStatus::Pending))
+    }
+}
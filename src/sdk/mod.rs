@@ -1,3 +1,8 @@
 pub mod error;
+pub mod key;
+pub mod ledger;
+pub mod light_client;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod mock;
 pub mod namada;
-pub mod utils;
+pub mod transfer;
@@ -1,36 +1,336 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
 use namada_sdk::{
+    address::Address,
+    args::{InputAmount, TxBuilder, TxTransparentTransferData},
     io::NullIo,
-    key::common::SecretKey,
-    masp::{fs::FsShieldedUtils, ShieldedContext},
-    wallet::{fs::FsWalletUtils, Wallet},
-    NamadaImpl,
+    key::{common::PublicKey, RefTo},
+    masp::fs::FsShieldedUtils,
+    rpc,
+    token::Amount,
+    tx::data::ResultCode,
+    wallet::fs::FsWalletUtils,
+    Namada, NamadaImpl,
 };
 use tendermint_rpc::HttpClient;
 
-pub struct Sdk {
-    pub faucet_sk: SecretKey,
-    namada_impl: NamadaImpl<HttpClient, FsWalletUtils, FsShieldedUtils, NullIo>,
+use crate::{
+    config::{Config, FeeSettings},
+    sdk::{
+        error::NamadaError,
+        ledger::{FeeEstimate, LedgerClient, Transfer, TxOutcome, TxStatus},
+        light_client::{LightClientVerifier, TxVerification},
+        transfer::{apply_fees, sign_reveal_pk, sign_transfer, submit},
+    },
+    state::pool::{AccountPool, FaucetAccount},
+};
+
+/// [`LedgerClient`] backed by a Namada node. When a light client is
+/// configured, balances, revealed keys and applied transactions are verified
+/// with it.
+pub struct NamadaClient {
+    sdk: Arc<NamadaImpl<HttpClient, FsWalletUtils, FsShieldedUtils, NullIo>>,
+    light_client: Option<LightClientVerifier>,
+    /// Transactions the node claimed applied against the verified chain.
+    rejected: Mutex<HashSet<String>>,
 }
 
-impl Sdk {
-    pub async fn new(
-        faucet_sk: SecretKey,
-        rpc_client: HttpClient,
-        wallet: Wallet<FsWalletUtils>,
-        shielded_ctx: ShieldedContext<FsShieldedUtils>,
-        io: NullIo,
+impl NamadaClient {
+    pub fn new(
+        sdk: Arc<NamadaImpl<HttpClient, FsWalletUtils, FsShieldedUtils, NullIo>>,
+        light_client: Option<LightClientVerifier>,
     ) -> Self {
         Self {
-            faucet_sk,
-            namada_impl: NamadaImpl::new(rpc_client, wallet, shielded_ctx.into(), io)
+            sdk,
+            light_client,
+            rejected: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn namada_ctx(&self) -> &NamadaImpl<HttpClient, FsWalletUtils, FsShieldedUtils, NullIo> {
+        &self.sdk
+    }
+
+    async fn verify_outcome(&self, outcome: TxOutcome) -> TxOutcome {
+        let (Some(light_client), Some(height), true) =
+            (&self.light_client, outcome.height, outcome.applied)
+        else {
+            return outcome;
+        };
+
+        let verification = light_client.verify_tx_applied(&outcome.hash, height).await;
+        let status = verified_status(&self.rejected, &outcome.hash, verification);
+
+        TxOutcome {
+            applied: status == TxStatus::Applied,
+            ..outcome
+        }
+    }
+}
+
+/// Transactions only count as applied once verified. Those that can't be
+/// verified yet stay pending and are checked again the next time their status
+/// is asked for, those contradicting the verified chain failed for good.
+fn verified_status(
+    rejected: &Mutex<HashSet<String>>,
+    hash: &str,
+    verification: anyhow::Result<TxVerification>,
+) -> TxStatus {
+    match verification {
+        Ok(TxVerification::Verified) => TxStatus::Applied,
+        Ok(TxVerification::Unavailable(e)) => {
+            tracing::warn!(
+                "Unable to verify transaction {}, keeping it pending: {:#}",
+                hash,
+                e
+            );
+            TxStatus::Pending
+        }
+        Err(e) => {
+            tracing::error!("Transaction {} failed verification: {:#}", hash, e);
+            rejected.lock().unwrap().insert(hash.to_string());
+            TxStatus::Failed
+        }
+    }
+}
+
+#[async_trait]
+impl LedgerClient for NamadaClient {
+    async fn native_token(&self) -> Result<Address, NamadaError> {
+        rpc::query_native_token(&self.sdk.clone_client())
+            .await
+            .map_err(|e| NamadaError::QueryInvalid(e.to_string()))
+    }
+
+    async fn balance(&self, token: &Address, owner: &Address) -> Result<Amount, NamadaError> {
+        match &self.light_client {
+            Some(light_client) => light_client
+                .verified_balance(token, owner)
+                .await
+                .map_err(|e| NamadaError::VerificationInvalid(format!("{:#}", e))),
+            None => rpc::get_token_balance(&self.sdk.clone_client(), token, owner, None)
+                .await
+                .map_err(|e| NamadaError::QueryInvalid(e.to_string())),
+        }
+    }
+
+    async fn denominate(&self, token: &Address, amount: Amount) -> String {
+        rpc::denominate_amount(&self.sdk.clone_client(), &NullIo, token, amount)
+            .await
+            .to_string()
+    }
+
+    async fn is_public_key_revealed(&self, owner: &Address) -> Result<bool, NamadaError> {
+        match &self.light_client {
+            Some(light_client) => light_client
+                .verified_public_key_revealed(owner)
+                .await
+                .map_err(|e| NamadaError::VerificationInvalid(format!("{:#}", e))),
+            None => rpc::is_public_key_revealed(&self.sdk.clone_client(), owner)
                 .await
-                .unwrap(),
+                .map_err(|e| NamadaError::QueryInvalid(e.to_string())),
         }
     }
 
-    pub async fn namada_ctx(
+    async fn estimate_fee(&self, fees: &FeeSettings) -> Result<FeeEstimate, NamadaError> {
+        let mut tx = self.sdk.tx_builder();
+        let amount = apply_fees(&self.sdk, &mut tx, fees)
+            .await
+            .map_err(NamadaError::FeeInvalid)?;
+
+        Ok(FeeEstimate {
+            token: tx.fee_token,
+            amount,
+        })
+    }
+
+    async fn transfer(
         &self,
-    ) -> &NamadaImpl<HttpClient, FsWalletUtils, FsShieldedUtils, NullIo> {
-        &self.namada_impl
+        signer: &PublicKey,
+        transfer: Transfer,
+        memo: &str,
+        fees: &FeeSettings,
+        dry_run: bool,
+    ) -> Result<TxOutcome, NamadaError> {
+        let amount = rpc::denominate_amount(
+            &self.sdk.clone_client(),
+            &NullIo,
+            &transfer.token,
+            transfer.amount,
+        )
+        .await;
+        let data = TxTransparentTransferData {
+            source: transfer.source,
+            target: transfer.target,
+            token: transfer.token,
+            amount: InputAmount::Unvalidated(amount),
+        };
+
+        let (tx, args) = sign_transfer(&self.sdk, signer, vec![data], memo, fees).await?;
+
+        // In dry run mode the signed transfer is never broadcast, the hash it
+        // would have is returned instead.
+        if dry_run {
+            return Ok(TxOutcome {
+                hash: tx.header_hash().to_string(),
+                applied: false,
+                height: None,
+            });
+        }
+
+        let outcome = submit(&self.sdk, tx, &args).await?;
+        Ok(self.verify_outcome(outcome).await)
+    }
+
+    async fn reveal_pk(
+        &self,
+        signer: &PublicKey,
+        public_key: PublicKey,
+        fees: &FeeSettings,
+    ) -> Result<TxOutcome, NamadaError> {
+        let (tx, args) = sign_reveal_pk(&self.sdk, signer, public_key, fees).await?;
+        let outcome = submit(&self.sdk, tx, &args).await?;
+        Ok(self.verify_outcome(outcome).await)
+    }
+
+    async fn tx_status(&self, hash: &str) -> Result<TxStatus, NamadaError> {
+        if self.rejected.lock().unwrap().contains(hash) {
+            return Ok(TxStatus::Failed);
+        }
+
+        let client = self.sdk.clone_client();
+        let applied = rpc::query_tx_events(&client, rpc::TxEventQuery::Applied(hash))
+            .await
+            .map_err(|e| NamadaError::QueryInvalid(e.to_string()))?;
+        if applied.is_none() {
+            return Ok(TxStatus::Pending);
+        }
+
+        let response = rpc::query_tx_response(&client, rpc::TxEventQuery::Applied(hash))
+            .await
+            .map_err(|e| NamadaError::QueryInvalid(e.to_string()))?;
+        if response.code != ResultCode::Ok {
+            return Ok(TxStatus::Failed);
+        }
+
+        match &self.light_client {
+            Some(light_client) => {
+                let verification = light_client
+                    .verify_tx_applied(hash, response.height.0)
+                    .await;
+                Ok(verified_status(&self.rejected, hash, verification))
+            }
+            None => Ok(TxStatus::Applied),
+        }
+    }
+}
+
+/// Builds the Namada context and adds the faucet accounts, and the treasury
+/// if any, to its wallet.
+pub async fn init(
+    config: &Config,
+) -> anyhow::Result<(
+    NamadaImpl<HttpClient, FsWalletUtils, FsShieldedUtils, NullIo>,
+    AccountPool,
+)> {
+    let http_client = HttpClient::new(config.rpc.clone()).context("unable to create RPC client")?;
+
+    // Setup wallet storage
+    let wallet = FsWalletUtils::new("wallet".into());
+
+    // Setup shielded context storage
+    let shielded_ctx = FsShieldedUtils::new("masp".into());
+
+    let null_io = NullIo;
+
+    let sdk = NamadaImpl::new(http_client, wallet, shielded_ctx, null_io)
+        .await
+        .context("unable to initialize Namada context")?
+        .chain_id(config.chain_id.clone());
+
+    let keys = std::iter::once(config.private_key.clone())
+        .chain(config.pool_keys.iter().cloned())
+        .enumerate()
+        .map(|(index, sk)| match index {
+            0 => ("faucet".to_string(), sk),
+            _ => (format!("faucet-{}", index), sk),
+        });
+
+    let mut accounts = Vec::new();
+    let mut wallet = sdk.wallet.write().await;
+    for (alias, sk) in keys {
+        let account = FaucetAccount::new(alias.clone(), sk.ref_to());
+        wallet
+            .insert_keypair(
+                alias.clone(),
+                true,
+                sk,
+                None,
+                Some(account.address.clone()),
+                None,
+            )
+            .with_context(|| format!("unable to add {} key to the wallet", alias))?;
+        accounts.push(account);
+    }
+
+    if let Some(policy) = &config.refill_policy {
+        let treasury_key = policy.treasury_key.clone();
+        let treasury_address = Address::from(&treasury_key.ref_to());
+        wallet
+            .insert_keypair(
+                "treasury".to_string(),
+                true,
+                treasury_key,
+                None,
+                Some(treasury_address),
+                None,
+            )
+            .context("unable to add treasury key to the wallet")?;
+    }
+
+    drop(wallet);
+
+    Ok((sdk, AccountPool::new(accounts, config.dispatch)))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn verified_transactions_are_applied() {
+        let rejected = Mutex::new(HashSet::new());
+
+        let status = verified_status(&rejected, "AB", Ok(TxVerification::Verified));
+
+        assert_eq!(status, TxStatus::Applied);
+    }
+
+    #[test]
+    fn transactions_that_cant_be_verified_stay_pending() {
+        let rejected = Mutex::new(HashSet::new());
+
+        let verification = Ok(TxVerification::Unavailable(anyhow!("node unreachable")));
+        let status = verified_status(&rejected, "AB", verification);
+
+        assert_eq!(status, TxStatus::Pending);
+        assert!(rejected.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn transactions_contradicting_the_verified_chain_fail_for_good() {
+        let rejected = Mutex::new(HashSet::new());
+
+        let status = verified_status(&rejected, "AB", Err(anyhow!("results don't match")));
+
+        assert_eq!(status, TxStatus::Failed);
+        assert!(rejected.lock().unwrap().contains("AB"));
     }
 }
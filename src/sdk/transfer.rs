@@ -0,0 +1,146 @@
+use namada_sdk::{
+    args::{self, InputAmount, TxTransparentTransferData},
+    io::NullIo,
+    key::common::PublicKey,
+    masp::fs::FsShieldedUtils,
+    rpc,
+    signing::default_sign,
+    tendermint::abci::Code,
+    token::Amount,
+    tx::{
+        data::{GasLimit, ResultCode},
+        ProcessTxResponse, Tx,
+    },
+    wallet::fs::FsWalletUtils,
+    Namada, NamadaImpl,
+};
+use tendermint_rpc::HttpClient;
+
+use crate::{
+    config::FeeSettings,
+    sdk::{error::NamadaError, ledger::TxOutcome},
+};
+
+/// Applies the configured fee settings to `tx`, falling back to the minimum
+/// gas price accepted by the chain, and returns the fee the transaction is
+/// expected to pay.
+pub async fn apply_fees(
+    sdk: &NamadaImpl<HttpClient, FsWalletUtils, FsShieldedUtils, NullIo>,
+    tx: &mut args::Tx,
+    fees: &FeeSettings,
+) -> Result<Amount, String> {
+    let client = sdk.clone_client();
+
+    if let Some(token) = &fees.token {
+        tx.fee_token = token.clone();
+    }
+    if let Some(gas_limit) = fees.gas_limit {
+        tx.gas_limit = GasLimit::from(gas_limit);
+    }
+
+    let gas_price = match fees.amount {
+        Some(amount) => {
+            let amount = Amount::from(amount);
+            let denominated = rpc::denominate_amount(&client, &NullIo, &tx.fee_token, amount).await;
+            tx.fee_amount = Some(InputAmount::Unvalidated(denominated));
+            amount
+        }
+        None => rpc::query_gas_cost(&client, &tx.fee_token)
+            .await
+            .ok_or_else(|| format!("{} can't be used to pay fees", tx.fee_token))?,
+    };
+
+    gas_price
+        .checked_mul(Amount::from(u64::from(tx.gas_limit)))
+        .ok_or_else(|| "fee amount overflow".to_string())
+}
+
+/// Builds and signs a transparent transfer signed and paid for by `signer`.
+pub async fn sign_transfer(
+    sdk: &NamadaImpl<HttpClient, FsWalletUtils, FsShieldedUtils, NullIo>,
+    signer: &PublicKey,
+    transfers: Vec<TxTransparentTransferData>,
+    memo: &str,
+    fees: &FeeSettings,
+) -> Result<(Tx, args::Tx), NamadaError> {
+    let mut transfer_tx_builder = sdk.new_transparent_transfer(transfers);
+    transfer_tx_builder.tx.memo = Some(memo.as_bytes().to_vec());
+    transfer_tx_builder.tx.signing_keys = vec![signer.clone()];
+    transfer_tx_builder.tx.wrapper_fee_payer = Some(signer.clone());
+
+    apply_fees(sdk, &mut transfer_tx_builder.tx, fees)
+        .await
+        .map_err(NamadaError::FeeInvalid)?;
+
+    let (mut transfer_tx, signing_data) = transfer_tx_builder
+        .build(sdk)
+        .await
+        .map_err(|e| NamadaError::TxBuildingInvalid(e.to_string()))?;
+    sdk.sign(
+        &mut transfer_tx,
+        &transfer_tx_builder.tx,
+        signing_data,
+        default_sign,
+        (),
+    )
+    .await
+    .map_err(|e| NamadaError::SigningDataInvalid(e.to_string()))?;
+
+    Ok((transfer_tx, transfer_tx_builder.tx))
+}
+
+/// Builds and signs a transaction revealing `public_key` on behalf of its
+/// owner. The transaction is signed and paid for by `signer`.
+pub async fn sign_reveal_pk(
+    sdk: &NamadaImpl<HttpClient, FsWalletUtils, FsShieldedUtils, NullIo>,
+    signer: &PublicKey,
+    public_key: PublicKey,
+    fees: &FeeSettings,
+) -> Result<(Tx, args::Tx), NamadaError> {
+    let mut reveal_tx_builder = sdk.new_reveal_pk(public_key);
+    reveal_tx_builder.tx.signing_keys = vec![signer.clone()];
+    reveal_tx_builder.tx.wrapper_fee_payer = Some(signer.clone());
+
+    apply_fees(sdk, &mut reveal_tx_builder.tx, fees)
+        .await
+        .map_err(NamadaError::FeeInvalid)?;
+
+    let (mut reveal_tx, signing_data) = reveal_tx_builder
+        .build(sdk)
+        .await
+        .map_err(|e| NamadaError::TxBuildingInvalid(e.to_string()))?;
+    sdk.sign(
+        &mut reveal_tx,
+        &reveal_tx_builder.tx,
+        signing_data,
+        default_sign,
+        (),
+    )
+    .await
+    .map_err(|e| NamadaError::SigningDataInvalid(e.to_string()))?;
+
+    Ok((reveal_tx, reveal_tx_builder.tx))
+}
+
+pub async fn submit(
+    sdk: &NamadaImpl<HttpClient, FsWalletUtils, FsShieldedUtils, NullIo>,
+    tx: Tx,
+    args: &args::Tx,
+) -> Result<TxOutcome, NamadaError> {
+    match sdk.submit(tx, args).await {
+        Ok(ProcessTxResponse::Applied(r)) => Ok(TxOutcome {
+            hash: r.hash.to_string(),
+            applied: r.code.eq(&ResultCode::Ok),
+            height: Some(r.height.0),
+        }),
+        Ok(ProcessTxResponse::Broadcast(r)) => Ok(TxOutcome {
+            hash: r.hash.to_string(),
+            applied: r.code.eq(&Code::Ok),
+            height: None,
+        }),
+        Ok(_) => Err(NamadaError::TxBroadcastingInvalid(
+            "unexpected response".to_string(),
+        )),
+        Err(e) => Err(NamadaError::TxBroadcastingInvalid(e.to_string())),
+    }
+}
@@ -6,9 +6,14 @@ use rand::SeedableRng;
 use rand_chacha::rand_core::OsRng;
 use rand_chacha::ChaCha20Core;
 
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc};
 use tokio::sync::RwLock;
 
+use namada_sdk::{
+    address::Address,
+    key::{common, SigScheme},
+};
+
 use crate::{
     app_state::AppState, entity::faucet::Faucet, error::api::ApiError,
     repository::faucet::FaucetRepository, repository::faucet::FaucetRepositoryTrait, utils,
@@ -35,6 +40,10 @@ impl FaucetService {
         Ok(Faucet::request(challenge, tag))
     }
 
+    pub fn generate_request_id(&mut self) -> String {
+        HEXLOWER.encode(&self.r.generate())
+    }
+
     fn compute_tag(&self, auth_key: &String, challenge: &[u8]) -> Vec<u8> {
         let key = auth::SecretKey::from_slice(auth_key.as_bytes())
             .expect("Should be able to convert key to bytes");
@@ -44,29 +53,56 @@ impl FaucetService {
     }
 
     pub fn verify_tag(&self, auth_key: &String, challenge: &String, tag: &String) -> bool {
-        let key = auth::SecretKey::from_slice(auth_key.as_bytes())
-            .expect("Should be able to convert key to bytes");
-
-        let decoded_tag = if let Ok(decoded_tag) = HEXLOWER.decode(tag.as_bytes()) {
-            let tag = Tag::from_slice(&decoded_tag);
-            match tag {
-                Ok(tag) => {
-                    let tag_bytes = tag.unprotected_as_bytes().to_vec();
-                    tag_bytes
-                }
-                Err(_) => return false,
-            }
+        let key = if let Ok(key) = auth::SecretKey::from_slice(auth_key.as_bytes()) {
+            key
         } else {
             return false;
         };
 
-        let tag = Tag::from_slice(&decoded_tag).expect("Should be able to convert bytes to tag");
+        let tag = if let Some(tag) = HEXLOWER
+            .decode(tag.as_bytes())
+            .ok()
+            .and_then(|tag| Tag::from_slice(&tag).ok())
+        {
+            tag
+        } else {
+            return false;
+        };
 
-        let decoded_challenge = HEXLOWER.decode(challenge.as_bytes()).expect("Test");
+        let decoded_challenge = if let Ok(challenge) = HEXLOWER.decode(challenge.as_bytes()) {
+            challenge
+        } else {
+            return false;
+        };
 
         auth::authenticate_verify(&tag, &key, &decoded_challenge).is_ok()
     }
 
+    /// Checks that `signature` is a signature of the challenge made with
+    /// `public_key` and that the key controls `target`. Only implicit
+    /// addresses are derived from a public key, so they are the only ones
+    /// that can be proven this way.
+    pub fn verify_ownership(
+        &self,
+        challenge: &str,
+        target: &Address,
+        public_key: &str,
+        signature: &str,
+    ) -> bool {
+        let (Ok(public_key), Ok(signature)) = (
+            common::PublicKey::from_str(public_key),
+            common::Signature::from_str(signature),
+        ) else {
+            return false;
+        };
+        let Ok(challenge) = HEXLOWER.decode(challenge.as_bytes()) else {
+            return false;
+        };
+
+        &Address::from(&public_key) == target
+            && common::SigScheme::verify_signature_raw(&public_key, &challenge, &signature).is_ok()
+    }
+
     pub fn verify_pow(&self, challenge: &String, solution: &String, difficulty: u64) -> bool {
         utils::pow::is_valid_proof_of_work(challenge, solution, difficulty)
     }
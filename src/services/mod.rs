@@ -1 +1,2 @@
 pub mod faucet;
+pub mod treasury;
@@ -0,0 +1,340 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use chrono::NaiveDate;
+use namada_sdk::{
+    address::Address,
+    key::{common::PublicKey, RefTo},
+    token::Amount,
+};
+use tokio::{sync::RwLock, time::Instant};
+
+use crate::{
+    config::RefillPolicy,
+    sdk::ledger::{LedgerClient, Transfer},
+    state::{faucet::FaucetSettings, pool::AccountPool},
+};
+
+/// Keeps the hot faucet accounts funded from the treasury account according
+/// to the configured refill policy.
+pub struct TreasuryService {
+    ledger: Arc<dyn LedgerClient>,
+    accounts: AccountPool,
+    settings: Arc<RwLock<FaucetSettings>>,
+    policy: RefillPolicy,
+    treasury_key: PublicKey,
+    last_refill: HashMap<Address, Instant>,
+    refilled_today: (NaiveDate, Amount),
+}
+
+impl TreasuryService {
+    pub fn new(
+        ledger: Arc<dyn LedgerClient>,
+        accounts: AccountPool,
+        settings: &Arc<RwLock<FaucetSettings>>,
+        policy: RefillPolicy,
+    ) -> Self {
+        let treasury_key = policy.treasury_key.ref_to();
+
+        Self {
+            ledger,
+            accounts,
+            settings: settings.clone(),
+            policy,
+            treasury_key,
+            last_refill: HashMap::new(),
+            refilled_today: (chrono::offset::Utc::now().date_naive(), Amount::zero()),
+        }
+    }
+
+    pub fn treasury_address(&self) -> Address {
+        Address::from(&self.treasury_key)
+    }
+
+    pub fn spawn(mut self) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.policy.interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.check_accounts().await {
+                    tracing::error!("Treasury refill check failed: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn check_accounts(&mut self) -> anyhow::Result<()> {
+        let token = match &self.policy.token {
+            Some(token) => token.clone(),
+            None => self.ledger.native_token().await?,
+        };
+
+        let threshold = base_units(self.policy.threshold)?;
+        let target = base_units(self.policy.target)?;
+        let max_per_day = self.policy.max_per_day.map(base_units).transpose()?;
+
+        let today = chrono::offset::Utc::now().date_naive();
+        if self.refilled_today.0 != today {
+            self.refilled_today = (today, Amount::zero());
+        }
+
+        let accounts = self
+            .accounts
+            .accounts()
+            .iter()
+            .map(|account| (account.alias.clone(), account.address.clone()))
+            .collect::<Vec<_>>();
+
+        for (alias, address) in accounts {
+            let balance = self.ledger.balance(&token, &address).await?;
+            if balance >= threshold {
+                continue;
+            }
+
+            let cooling_down = self
+                .last_refill
+                .get(&address)
+                .is_some_and(|last_refill| last_refill.elapsed() < self.policy.cooldown);
+            if cooling_down {
+                continue;
+            }
+
+            let amount = target.checked_sub(balance).unwrap_or_default();
+            let refilled = self
+                .refilled_today
+                .1
+                .checked_add(amount)
+                .unwrap_or(self.refilled_today.1);
+            if let Some(max_per_day) = max_per_day {
+                if refilled > max_per_day {
+                    tracing::warn!(
+                        "Skipping refill of {}, daily treasury refill limit reached",
+                        alias
+                    );
+                    continue;
+                }
+            }
+
+            let (sent, tx_hash) = self.refill(&address, &token, amount).await;
+
+            tracing::info!(
+                target: "audit",
+                account = %alias,
+                address = %address,
+                treasury = %self.treasury_address(),
+                token = %token,
+                amount = %amount.to_string_native(),
+                sent = sent,
+                tx_hash = tx_hash.as_deref(),
+                "treasury refill"
+            );
+
+            self.last_refill.insert(address, Instant::now());
+            // A broadcast refill may still be applied later, it counts toward
+            // the daily limit right away.
+            if tx_hash.is_some() {
+                self.refilled_today.1 = refilled;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn refill(
+        &self,
+        target: &Address,
+        token: &Address,
+        amount: Amount,
+    ) -> (bool, Option<String>) {
+        let transfer = Transfer {
+            source: self.treasury_address(),
+            target: target.clone(),
+            token: token.clone(),
+            amount,
+        };
+
+        let fees = self.settings.read().await.fees.clone();
+
+        match self
+            .ledger
+            .transfer(
+                &self.treasury_key,
+                transfer,
+                "Treasury refill",
+                &fees,
+                false,
+            )
+            .await
+        {
+            Ok(outcome) => (outcome.applied, Some(outcome.hash)),
+            Err(e) => {
+                tracing::error!("Unable to send treasury refill: {}", e);
+                (false, None)
+            }
+        }
+    }
+}
+
+/// Whole tokens to base units, the config rejects amounts that don't fit.
+fn base_units(amount: u64) -> anyhow::Result<Amount> {
+    amount
+        .checked_mul(10_u64.pow(6))
+        .map(Amount::from)
+        .ok_or_else(|| anyhow::anyhow!("refill amount {} doesn't fit in base units", amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use namada_sdk::key::{common, ed25519, SigScheme};
+    use rand::rngs::OsRng;
+
+    use super::*;
+    use crate::{
+        config::FeeSettings,
+        sdk::{ledger::TxStatus, mock::MockLedger},
+        state::pool::{DispatchStrategy, FaucetAccount},
+    };
+
+    const UNIT: u64 = 1_000_000;
+
+    struct Treasury {
+        service: TreasuryService,
+        ledger: Arc<MockLedger>,
+        token: Address,
+        accounts: Vec<Address>,
+    }
+
+    fn secret_key() -> common::SecretKey {
+        common::SecretKey::Ed25519(ed25519::SigScheme::generate(&mut OsRng))
+    }
+
+    /// `accounts` empty hot accounts refilled to 10 tokens below 5.
+    fn treasury(accounts: usize, cooldown: Duration, max_per_day: Option<u64>) -> Treasury {
+        let token = Address::from(&secret_key().ref_to());
+        let ledger = Arc::new(MockLedger::new(token.clone(), Amount::from(1_000)));
+
+        let accounts = AccountPool::new(
+            (0..accounts)
+                .map(|index| FaucetAccount::new(format!("hot-{}", index), secret_key().ref_to()))
+                .collect(),
+            DispatchStrategy::RoundRobin,
+        );
+        let policy = RefillPolicy {
+            treasury_key: secret_key(),
+            token: None,
+            threshold: 5,
+            target: 10,
+            cooldown,
+            max_per_day,
+            interval: Duration::from_secs(60),
+        };
+        ledger.set_balance(
+            &token,
+            &Address::from(&policy.treasury_key.ref_to()),
+            Amount::from(1_000 * UNIT),
+        );
+        let settings = Arc::new(RwLock::new(FaucetSettings {
+            difficulty: 1,
+            withdraw_limit: UNIT,
+            tokens: HashMap::new(),
+            fees: FeeSettings::default(),
+            memo_template: String::new(),
+            campaign: String::new(),
+            allowed_targets: HashSet::new(),
+            target_balance_limit: None,
+            require_ownership_proof: false,
+            reveal_pk: false,
+        }));
+
+        let addresses = accounts
+            .accounts()
+            .iter()
+            .map(|account| account.address.clone())
+            .collect();
+        Treasury {
+            service: TreasuryService::new(ledger.clone(), accounts, &settings, policy),
+            ledger,
+            token,
+            accounts: addresses,
+        }
+    }
+
+    impl Treasury {
+        async fn balance(&self, index: usize) -> Amount {
+            self.ledger
+                .balance(&self.token, &self.accounts[index])
+                .await
+                .unwrap()
+        }
+
+        fn drain(&self, index: usize) {
+            self.ledger
+                .set_balance(&self.token, &self.accounts[index], Amount::zero());
+        }
+    }
+
+    #[tokio::test]
+    async fn accounts_below_the_threshold_are_refilled_to_the_target() {
+        let mut treasury = treasury(1, Duration::ZERO, None);
+        treasury
+            .ledger
+            .set_balance(&treasury.token, &treasury.accounts[0], Amount::from(UNIT));
+
+        treasury.service.check_accounts().await.unwrap();
+
+        assert_eq!(treasury.balance(0).await, Amount::from(10 * UNIT));
+
+        // Above the threshold again, nothing more is sent.
+        treasury.service.check_accounts().await.unwrap();
+        assert_eq!(treasury.ledger.transactions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn accounts_are_not_refilled_again_during_the_cooldown() {
+        let mut treasury = treasury(1, Duration::from_secs(3600), None);
+
+        treasury.service.check_accounts().await.unwrap();
+        treasury.drain(0);
+        treasury.service.check_accounts().await.unwrap();
+
+        assert_eq!(treasury.ledger.transactions().len(), 1);
+        assert_eq!(treasury.balance(0).await, Amount::zero());
+    }
+
+    #[tokio::test]
+    async fn refills_stop_at_the_daily_limit() {
+        let mut treasury = treasury(3, Duration::ZERO, Some(25));
+
+        treasury.service.check_accounts().await.unwrap();
+
+        // Two refills of 10 tokens fit in the limit, a third doesn't.
+        assert_eq!(treasury.ledger.transactions().len(), 2);
+        assert_eq!(treasury.balance(0).await, Amount::from(10 * UNIT));
+        assert_eq!(treasury.balance(1).await, Amount::from(10 * UNIT));
+        assert_eq!(treasury.balance(2).await, Amount::zero());
+
+        treasury.drain(0);
+        treasury.service.check_accounts().await.unwrap();
+        assert_eq!(treasury.ledger.transactions().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn pending_refills_count_toward_the_daily_limit() {
+        let mut treasury = treasury(2, Duration::ZERO, Some(15));
+        treasury.ledger.hold(true);
+
+        treasury.service.check_accounts().await.unwrap();
+
+        let transactions = treasury.ledger.transactions();
+        assert_eq!(transactions.len(), 1);
+
+        treasury
+            .ledger
+            .settle(&transactions[0], TxStatus::Applied)
+            .unwrap();
+        treasury.ledger.hold(false);
+        treasury.service.check_accounts().await.unwrap();
+        assert_eq!(treasury.ledger.transactions().len(), 1);
+    }
+}
@@ -0,0 +1,52 @@
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::Mutex;
+
+use crate::dto::admin::FaucetAccountDto;
+
+/// Balances served by `/metrics` are refreshed at most this often.
+const BALANCE_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Last balances of the faucet accounts. Scrapes within the TTL reuse them
+/// and concurrent scrapes wait for a single refresh, so the ledger is queried
+/// once per interval however often `/metrics` is hit.
+#[derive(Clone)]
+pub struct BalanceCache {
+    snapshot: Arc<Mutex<Option<(Instant, Vec<FaucetAccountDto>)>>>,
+}
+
+impl BalanceCache {
+    pub fn new() -> Self {
+        Self {
+            snapshot: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub async fn get_or_refresh<E, F, Fut>(&self, refresh: F) -> Result<Vec<FaucetAccountDto>, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<FaucetAccountDto>, E>>,
+    {
+        let mut snapshot = self.snapshot.lock().await;
+        if let Some((fetched_at, accounts)) = snapshot.as_ref() {
+            if fetched_at.elapsed() < BALANCE_CACHE_TTL {
+                return Ok(accounts.clone());
+            }
+        }
+
+        let accounts = refresh().await?;
+        *snapshot = Some((Instant::now(), accounts.clone()));
+
+        Ok(accounts)
+    }
+}
+
+impl Default for BalanceCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::broadcast;
+
+use crate::dto::faucet::TransferEventDto;
+
+/// Requests whose events are kept for late subscribers.
+const MAX_TRACKED_REQUESTS: usize = 10_000;
+
+/// Status updates of faucet transfers by request ID. Subscribers get the
+/// events published so far followed by the live ones. Requests sent with an
+/// idempotency key can also be followed by that key, before the request ID
+/// is known.
+#[derive(Clone)]
+pub struct TransferEvents {
+    history: Arc<Mutex<History>>,
+    sender: broadcast::Sender<TransferEventDto>,
+}
+
+#[derive(Default)]
+struct History {
+    events: HashMap<String, Vec<TransferEventDto>>,
+    order: VecDeque<String>,
+    /// Request ID by idempotency key, and the other way around.
+    request_ids: HashMap<String, String>,
+    idempotency_keys: HashMap<String, String>,
+}
+
+impl TransferEvents {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(1024);
+        Self {
+            history: Arc::new(Mutex::new(History::default())),
+            sender,
+        }
+    }
+
+    pub fn publish(&self, event: TransferEventDto) {
+        let mut history = self.history.lock().unwrap();
+        if !history.events.contains_key(&event.request_id) {
+            if history.order.len() >= MAX_TRACKED_REQUESTS {
+                if let Some(oldest) = history.order.pop_front() {
+                    history.events.remove(&oldest);
+                    if let Some(key) = history.idempotency_keys.remove(&oldest) {
+                        history.request_ids.remove(&key);
+                    }
+                }
+            }
+            history.order.push_back(event.request_id.clone());
+        }
+        history
+            .events
+            .entry(event.request_id.clone())
+            .or_default()
+            .push(event.clone());
+
+        // Nobody listening is fine, the history has the event.
+        let _ = self.sender.send(event);
+    }
+
+    /// Lets the request be followed by `idempotency_key`. Must be called
+    /// before its first event is published.
+    pub fn alias(&self, idempotency_key: &str, request_id: &str) {
+        let mut history = self.history.lock().unwrap();
+        history
+            .request_ids
+            .insert(idempotency_key.to_string(), request_id.to_string());
+        history
+            .idempotency_keys
+            .insert(request_id.to_string(), idempotency_key.to_string());
+    }
+
+    pub fn request_id(&self, idempotency_key: &str) -> Option<String> {
+        let history = self.history.lock().unwrap();
+        history.request_ids.get(idempotency_key).cloned()
+    }
+
+    pub fn latest(&self, request_id: &str) -> Option<TransferEventDto> {
+        let history = self.history.lock().unwrap();
+        history.events.get(request_id)?.last().cloned()
+    }
+
+    /// Past events of `request_id` and a receiver for the next ones, or
+    /// `None` for an unknown request.
+    pub fn subscribe(
+        &self,
+        request_id: &str,
+    ) -> Option<(Vec<TransferEventDto>, broadcast::Receiver<TransferEventDto>)> {
+        // Subscribing under the lock means no event is missed or repeated.
+        let history = self.history.lock().unwrap();
+        let events = history.events.get(request_id)?.clone();
+
+        Some((events, self.sender.subscribe()))
+    }
+
+    /// Like [`TransferEvents::subscribe`] for the request sent with
+    /// `idempotency_key`. Unknown keys get no past events, the request may
+    /// not have been sent yet.
+    pub fn subscribe_by_key(
+        &self,
+        idempotency_key: &str,
+    ) -> (Vec<TransferEventDto>, broadcast::Receiver<TransferEventDto>) {
+        let history = self.history.lock().unwrap();
+        let events = history
+            .request_ids
+            .get(idempotency_key)
+            .and_then(|request_id| history.events.get(request_id))
+            .cloned()
+            .unwrap_or_default();
+
+        (events, self.sender.subscribe())
+    }
+}
+
+impl Default for TransferEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
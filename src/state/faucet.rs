@@ -1,50 +1,111 @@
 use crate::{
-    app_state::AppState, repository::faucet::FaucetRepository,
-    repository::faucet::FaucetRepositoryTrait, services::faucet::FaucetService,
+    app_state::AppState,
+    config::{Config, FeeSettings, TargetBalanceLimit},
+    entity::address::AddressKind,
+    repository::access_list::{AccessListRepository, AccessListRepositoryTrait},
+    repository::api_key::{ApiKeyRepository, ApiKeyRepositoryTrait},
+    repository::faucet::FaucetRepository,
+    repository::faucet::FaucetRepositoryTrait,
+    repository::idempotency::{IdempotencyRepository, IdempotencyRepositoryTrait},
+    sdk::ledger::LedgerClient,
+    services::faucet::FaucetService,
+    state::{balances::BalanceCache, events::TransferEvents, pool::AccountPool},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
 };
-use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use namada_sdk::{
-    address::Address, io::NullIo, masp::fs::FsShieldedUtils, wallet::fs::FsWalletUtils, NamadaImpl,
-};
-use tendermint_rpc::HttpClient;
+use namada_sdk::address::Address;
 
 #[derive(Clone)]
 pub struct FaucetState {
     pub faucet_service: FaucetService,
     pub faucet_repo: FaucetRepository,
-    pub faucet_address: Address,
-    pub sdk: Arc<NamadaImpl<HttpClient, FsWalletUtils, FsShieldedUtils, NullIo>>,
+    pub access_list_repo: AccessListRepository,
+    pub api_key_repo: ApiKeyRepository,
+    pub idempotency_repo: IdempotencyRepository,
+    pub accounts: AccountPool,
+    pub treasury: Option<Address>,
+    pub ledger: Arc<dyn LedgerClient>,
+    pub events: TransferEvents,
+    pub balances: BalanceCache,
     pub auth_key: String,
-    pub difficulty: u64,
+    pub admin_key: Option<String>,
+    pub access_list_path: Option<PathBuf>,
+    pub settings: Arc<RwLock<FaucetSettings>>,
     pub chain_id: String,
     pub chain_start: i64,
+    pub dry_run: bool,
+}
+
+/// Settings that can be swapped while the server is running.
+#[derive(Clone)]
+pub struct FaucetSettings {
+    pub difficulty: u64,
     pub withdraw_limit: u64,
+    pub tokens: HashMap<String, Address>,
+    pub fees: FeeSettings,
+    pub memo_template: String,
+    pub campaign: String,
+    pub allowed_targets: HashSet<AddressKind>,
+    pub target_balance_limit: Option<TargetBalanceLimit>,
+    pub require_ownership_proof: bool,
+    pub reveal_pk: bool,
 }
 
 impl FaucetState {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         data: &Arc<RwLock<AppState>>,
-        faucet_address: Address,
-        sdk: NamadaImpl<HttpClient, FsWalletUtils, FsShieldedUtils, NullIo>,
+        accounts: AccountPool,
+        treasury: Option<Address>,
+        ledger: Arc<dyn LedgerClient>,
         auth_key: String,
-        difficulty: u64,
+        admin_key: Option<String>,
+        access_list_path: Option<PathBuf>,
+        settings: &Arc<RwLock<FaucetSettings>>,
         chain_id: String,
         chain_start: i64,
-        withdraw_limit: u64,
+        dry_run: bool,
     ) -> Self {
         Self {
             faucet_service: FaucetService::new(data),
             faucet_repo: FaucetRepository::new(data),
-            faucet_address,
-            sdk: Arc::new(sdk),
+            access_list_repo: AccessListRepository::new(data),
+            api_key_repo: ApiKeyRepository::new(data),
+            idempotency_repo: IdempotencyRepository::new(data),
+            accounts,
+            treasury,
+            ledger,
+            events: TransferEvents::new(),
+            balances: BalanceCache::new(),
             auth_key,
-            difficulty,
+            admin_key,
+            access_list_path,
+            settings: settings.clone(),
             chain_id,
             chain_start,
-            withdraw_limit: withdraw_limit * 10_u64.pow(6),
+            dry_run,
+        }
+    }
+}
+
+impl From<&Config> for FaucetSettings {
+    fn from(config: &Config) -> Self {
+        Self {
+            difficulty: config.difficulty,
+            withdraw_limit: config.withdraw_limit * 10_u64.pow(6),
+            tokens: config.tokens.clone(),
+            fees: config.fees.clone(),
+            memo_template: config.memo_template.clone(),
+            campaign: config.campaign.clone(),
+            allowed_targets: config.allowed_targets.clone(),
+            target_balance_limit: config.target_balance_limit,
+            require_ownership_proof: config.require_ownership_proof,
+            reveal_pk: config.reveal_pk,
         }
     }
 }
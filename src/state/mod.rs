@@ -1 +1,4 @@
+pub mod balances;
+pub mod events;
 pub mod faucet;
+pub mod pool;
@@ -0,0 +1,209 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+use namada_sdk::{address::Address, key::common::PublicKey};
+use serde::Deserialize;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DispatchStrategy {
+    #[default]
+    RoundRobin,
+    LeastRecentlyUsed,
+}
+
+pub struct FaucetAccount {
+    pub alias: String,
+    pub address: Address,
+    pub public_key: PublicKey,
+    in_flight: AtomicU64,
+    last_used: Mutex<Option<Instant>>,
+}
+
+/// Signing accounts the faucet disburses from. Transfers are spread across
+/// them so that a single key doesn't serialize every transaction.
+#[derive(Clone)]
+pub struct AccountPool {
+    accounts: Arc<Vec<FaucetAccount>>,
+    strategy: DispatchStrategy,
+    next: Arc<AtomicUsize>,
+}
+
+/// Marks an account as busy until dropped.
+pub struct AccountGuard<'a> {
+    pub account: &'a FaucetAccount,
+}
+
+impl FaucetAccount {
+    pub fn new(alias: String, public_key: PublicKey) -> Self {
+        Self {
+            alias,
+            address: Address::from(&public_key),
+            public_key,
+            in_flight: AtomicU64::new(0),
+            last_used: Mutex::new(None),
+        }
+    }
+
+    pub fn in_flight(&self) -> u64 {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    fn last_used(&self) -> Option<Instant> {
+        *self.last_used.lock().unwrap()
+    }
+}
+
+impl AccountPool {
+    pub fn new(accounts: Vec<FaucetAccount>, strategy: DispatchStrategy) -> Self {
+        assert!(!accounts.is_empty(), "the account pool can't be empty");
+
+        Self {
+            accounts: Arc::new(accounts),
+            strategy,
+            next: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn accounts(&self) -> &[FaucetAccount] {
+        &self.accounts
+    }
+
+    /// The account configured with `private_key`, used for anything that
+    /// isn't a disbursement.
+    pub fn primary(&self) -> &FaucetAccount {
+        &self.accounts[0]
+    }
+
+    pub fn contains(&self, address: &Address) -> bool {
+        self.accounts
+            .iter()
+            .any(|account| &account.address == address)
+    }
+
+    pub fn acquire(&self) -> AccountGuard<'_> {
+        self.acquire_except(&[])
+            .expect("the account pool can't be empty")
+    }
+
+    /// Like [`AccountPool::acquire`], passing over the accounts in `skipped`.
+    /// `None` once every account was skipped.
+    pub fn acquire_except(&self, skipped: &[Address]) -> Option<AccountGuard<'_>> {
+        let available = |account: &&FaucetAccount| !skipped.contains(&account.address);
+        let account = match self.strategy {
+            DispatchStrategy::RoundRobin => {
+                let start = self.next.fetch_add(1, Ordering::Relaxed);
+                (0..self.accounts.len())
+                    .map(|offset| &self.accounts[(start + offset) % self.accounts.len()])
+                    .find(available)?
+            }
+            DispatchStrategy::LeastRecentlyUsed => self
+                .accounts
+                .iter()
+                .filter(available)
+                .min_by_key(|account| (account.in_flight(), account.last_used()))?,
+        };
+
+        *account.last_used.lock().unwrap() = Some(Instant::now());
+        account.in_flight.fetch_add(1, Ordering::Relaxed);
+
+        Some(AccountGuard { account })
+    }
+}
+
+impl Drop for AccountGuard<'_> {
+    fn drop(&mut self) {
+        self.account.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_sdk::key::{common, ed25519, RefTo, SigScheme};
+    use rand::rngs::OsRng;
+
+    use super::*;
+
+    fn pool(size: usize, strategy: DispatchStrategy) -> AccountPool {
+        let accounts = (0..size)
+            .map(|index| {
+                let sk = common::SecretKey::Ed25519(ed25519::SigScheme::generate(&mut OsRng));
+                FaucetAccount::new(format!("account-{}", index), sk.ref_to())
+            })
+            .collect();
+        AccountPool::new(accounts, strategy)
+    }
+
+    fn alias(guard: AccountGuard<'_>) -> String {
+        guard.account.alias.clone()
+    }
+
+    #[test]
+    fn round_robin_cycles_through_the_accounts() {
+        let pool = pool(3, DispatchStrategy::RoundRobin);
+
+        let aliases: Vec<_> = (0..4).map(|_| alias(pool.acquire())).collect();
+
+        assert_eq!(
+            aliases,
+            ["account-0", "account-1", "account-2", "account-0"]
+        );
+    }
+
+    #[test]
+    fn least_recently_used_prefers_idle_accounts() {
+        let pool = pool(3, DispatchStrategy::LeastRecentlyUsed);
+
+        let first = pool.acquire();
+        let second = pool.acquire();
+        assert_eq!(first.account.alias, "account-0");
+        assert_eq!(second.account.alias, "account-1");
+
+        // The busy accounts are passed over, then the one used longest ago.
+        assert_eq!(alias(pool.acquire()), "account-2");
+        drop(first);
+        assert_eq!(alias(pool.acquire()), "account-0");
+    }
+
+    #[test]
+    fn guards_release_their_account_when_dropped() {
+        let pool = pool(1, DispatchStrategy::RoundRobin);
+
+        let first = pool.acquire();
+        let second = pool.acquire();
+        assert_eq!(pool.primary().in_flight(), 2);
+
+        drop(first);
+        assert_eq!(pool.primary().in_flight(), 1);
+        drop(second);
+        assert_eq!(pool.primary().in_flight(), 0);
+    }
+
+    #[test]
+    fn skipped_accounts_are_passed_over() {
+        for strategy in [
+            DispatchStrategy::RoundRobin,
+            DispatchStrategy::LeastRecentlyUsed,
+        ] {
+            let pool = pool(2, strategy);
+            let skipped = [pool.primary().address.clone()];
+
+            for _ in 0..3 {
+                let guard = pool.acquire_except(&skipped).unwrap();
+                assert_eq!(guard.account.alias, "account-1");
+            }
+
+            let all: Vec<_> = pool
+                .accounts()
+                .iter()
+                .map(|account| account.address.clone())
+                .collect();
+            assert!(pool.acquire_except(&all).is_none());
+        }
+    }
+}
@@ -0,0 +1,31 @@
+pub const DEFAULT_MEMO_TEMPLATE: &str = "Transfer from faucet";
+pub const DEFAULT_AIRDROP_MEMO: &str = "Airdrop from faucet";
+
+/// Values available to memo templates as `{request_id}`, `{chain_id}`,
+/// `{campaign}`, `{target}`, `{token}` and `{amount}`.
+pub struct MemoContext<'a> {
+    pub request_id: &'a str,
+    pub chain_id: &'a str,
+    pub campaign: &'a str,
+    pub target: &'a str,
+    pub token: &'a str,
+    pub amount: u64,
+}
+
+pub fn render_memo(template: &str, context: &MemoContext) -> String {
+    template
+        .replace("{request_id}", context.request_id)
+        .replace("{chain_id}", context.chain_id)
+        .replace("{campaign}", context.campaign)
+        .replace("{target}", context.target)
+        .replace("{token}", context.token)
+        .replace("{amount}", &context.amount.to_string())
+}
+
+/// Airdrop transactions batch several targets, so only `{chain_id}` and
+/// `{campaign}` are filled in.
+pub fn render_airdrop_memo(template: &str, chain_id: &str, campaign: &str) -> String {
+    template
+        .replace("{chain_id}", chain_id)
+        .replace("{campaign}", campaign)
+}
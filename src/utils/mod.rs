@@ -1 +1,3 @@
+pub mod memo;
 pub mod pow;
+pub mod rate_limit;
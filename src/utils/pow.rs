@@ -1,42 +1,8 @@
-use data_encoding::HEXLOWER;
-use orion::hazardous::hash::sha2::sha256::Sha256;
+pub use namada_faucet_pow::leading_zero_nibbles;
 
-pub fn is_valid_proof_of_work(challenge: &String, solution: &String, difficulty: u64) -> bool {
-    let decoded_challenge = if let Ok(challenge) = HEXLOWER.decode(challenge.as_bytes()) {
-        challenge
-    } else {
-        return false;
-    };
-    let decoded_solution = if let Ok(solution) = HEXLOWER.decode(solution.as_bytes()) {
-        solution
-    } else {
-        return false;
-    };
-
-    let mut hasher = Sha256::new();
-    hasher
-        .update(&decoded_challenge)
-        .expect("Should be able to hash bytes");
-    hasher
-        .update(&decoded_solution)
-        .expect("Should be able to hash bytes");
-
-    let hash = hasher.finalize().expect("Should be able to hash bytes");
-
-    // sketchy
-    let hash_bytes = HEXLOWER.encode(hash.as_ref());
-
-    // TODO: rewrite with bit mask
-    for byte in hash_bytes
-        .as_bytes()
-        .iter()
-        .take(difficulty as usize)
-        .cloned()
-    {
-        if byte != b'0' {
-            return false;
-        }
-    }
-
-    true
+/// Checks that `sha256(challenge || solution)` starts with `difficulty` zero
+/// hex digits. Both inputs are lowercase hex; anything else is invalid. The
+/// check lives in `namada-faucet-pow` so browser solvers run the same code.
+pub fn is_valid_proof_of_work(challenge: &str, solution: &str, difficulty: u64) -> bool {
+    namada_faucet_pow::is_valid_hex(challenge, solution, difficulty)
 }
@@ -0,0 +1,65 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use axum::{extract::State, http::Request, middleware::Next, response::Response};
+use tokio::{
+    sync::Mutex,
+    time::{sleep_until, Instant},
+};
+
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// Fixed window rate limiter whose limit can be changed while the server is
+/// running. Like tower's `RateLimit`, requests over the limit wait for the next
+/// window instead of being rejected.
+#[derive(Clone)]
+pub struct RateLimiter {
+    rps: Arc<AtomicU64>,
+    window: Arc<Mutex<(Instant, u64)>>,
+}
+
+impl RateLimiter {
+    pub fn new(rps: u64) -> Self {
+        Self {
+            rps: Arc::new(AtomicU64::new(rps)),
+            window: Arc::new(Mutex::new((Instant::now(), 0))),
+        }
+    }
+
+    pub fn set_rps(&self, rps: u64) {
+        self.rps.store(rps, Ordering::Relaxed);
+    }
+
+    pub async fn acquire(&self) {
+        loop {
+            let mut window = self.window.lock().await;
+            let now = Instant::now();
+            if now >= window.0 + WINDOW {
+                *window = (now, 0);
+            }
+
+            if window.1 < self.rps.load(Ordering::Relaxed) {
+                window.1 += 1;
+                return;
+            }
+
+            let next_window = window.0 + WINDOW;
+            drop(window);
+            sleep_until(next_window).await;
+        }
+    }
+
+    pub async fn middleware<B>(
+        State(rate_limiter): State<RateLimiter>,
+        request: Request<B>,
+        next: Next<B>,
+    ) -> Response {
+        rate_limiter.acquire().await;
+        next.run(request).await
+    }
+}
@@ -0,0 +1,39 @@
+use clap::Parser;
+use namada_faucet::cli::{Cli, Command};
+
+#[test]
+fn serve_flags_are_accepted_after_the_subcommand() {
+    let cli =
+        Cli::try_parse_from(["namada-faucet", "serve", "--port", "5001", "--dry-run"]).unwrap();
+
+    assert!(matches!(cli.command, Some(Command::Serve)));
+    assert_eq!(cli.config.port, Some(5001));
+    assert!(cli.config.dry_run);
+}
+
+#[test]
+fn serve_flags_are_accepted_before_the_subcommand() {
+    let cli = Cli::try_parse_from(["namada-faucet", "--port", "5001", "serve"]).unwrap();
+
+    assert!(matches!(cli.command, Some(Command::Serve)));
+    assert_eq!(cli.config.port, Some(5001));
+}
+
+#[cfg(feature = "client")]
+#[test]
+fn solve_keeps_its_own_difficulty() {
+    let cli = Cli::try_parse_from([
+        "namada-faucet",
+        "solve",
+        "--challenge",
+        "00",
+        "--difficulty",
+        "3",
+    ])
+    .unwrap();
+
+    match cli.command {
+        Some(Command::Solve(args)) => assert_eq!(args.difficulty, 3),
+        _ => panic!("expected the solve command"),
+    }
+}
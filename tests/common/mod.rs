@@ -0,0 +1,339 @@
+#![allow(dead_code)]
+
+pub mod rpc;
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{Method, Request, StatusCode},
+    Router,
+};
+use data_encoding::HEXLOWER;
+use namada_faucet::{
+    app::ApplicationServer,
+    app_state::AppState,
+    config::FeeSettings,
+    entity::{address::DEFAULT_ALLOWED_TARGETS, api_key::ApiKey},
+    sdk::{ledger::LedgerClient, mock::MockLedger},
+    state::{
+        faucet::{FaucetSettings, FaucetState},
+        pool::{AccountPool, DispatchStrategy, FaucetAccount},
+    },
+    utils::{memo::DEFAULT_MEMO_TEMPLATE, pow::is_valid_proof_of_work, rate_limit::RateLimiter},
+};
+use namada_sdk::{
+    address::Address,
+    key::{common, ed25519, RefTo, SigScheme},
+    token::Amount,
+};
+use rand::rngs::OsRng;
+use serde_json::Value;
+use tokio::{sync::RwLock, task::JoinHandle};
+use tower::ServiceExt;
+
+pub const AUTH_KEY: &str = "0123456789abcdef0123456789abcdef";
+pub const ADMIN_KEY: &str = "admin-key";
+pub const DIFFICULTY: u64 = 1;
+pub const FEE: u64 = 1_000;
+pub const FAUCET_BALANCE: u64 = 1_000_000_000_000;
+
+/// The faucet router wired to an in-memory ledger, driven without binding a
+/// socket.
+pub struct TestApp {
+    pub router: Router,
+    pub ledger: Arc<MockLedger>,
+    pub native_token: Address,
+    pub faucet: Address,
+    pub settings: Arc<RwLock<FaucetSettings>>,
+    pub db: Arc<RwLock<AppState>>,
+}
+
+impl TestApp {
+    pub fn new() -> Self {
+        Self::with_rps(u64::MAX)
+    }
+
+    pub fn with_rps(rps: u64) -> Self {
+        let native_token = implicit_address();
+        let faucet_key = secret_key();
+        let faucet = Address::from(&faucet_key.ref_to());
+
+        let ledger = Arc::new(MockLedger::new(native_token.clone(), Amount::from(FEE)));
+        ledger.set_balance(&native_token, &faucet, Amount::from(FAUCET_BALANCE));
+
+        let settings = Arc::new(RwLock::new(default_settings()));
+        let db = Arc::new(RwLock::new(AppState::default()));
+        let router = faucet_router(
+            ledger.clone(),
+            &faucet_key.ref_to(),
+            &settings,
+            &db,
+            rps,
+            false,
+        );
+
+        Self {
+            router,
+            ledger,
+            native_token,
+            faucet,
+            settings,
+            db,
+        }
+    }
+
+    pub async fn get(&self, uri: &str) -> (StatusCode, Value) {
+        self.request(Method::GET, uri, None, &[]).await
+    }
+
+    pub async fn post(&self, uri: &str, body: Value) -> (StatusCode, Value) {
+        self.request(Method::POST, uri, Some(body), &[]).await
+    }
+
+    pub async fn request(
+        &self,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+        headers: &[(&str, &str)],
+    ) -> (StatusCode, Value) {
+        send(&self.router, method, uri, body, headers).await
+    }
+
+    /// Raw response body, for endpoints that don't answer with JSON.
+    pub async fn get_text(&self, uri: &str) -> (StatusCode, String) {
+        let (status, body) = self.stream_text(uri).await;
+        (status, body.await.unwrap())
+    }
+
+    /// Like [`TestApp::get_text`], returning once the handler answered and
+    /// reading the body in the background, for streams that end later.
+    pub async fn stream_text(&self, uri: &str) -> (StatusCode, JoinHandle<String>) {
+        let mut request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 40000))));
+
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = tokio::spawn(async move {
+            let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            String::from_utf8_lossy(&bytes).to_string()
+        });
+
+        (status, body)
+    }
+
+    /// Fetches a challenge and solves it, returning the request body for a
+    /// transfer of `amount` to `target`.
+    pub async fn solved_request(&self, target: &Address, amount: u64) -> Value {
+        let (status, challenge) = self.get("/api/v1/faucet").await;
+        assert_eq!(status, StatusCode::OK);
+
+        let solution = solve(challenge["challenge"].as_str().unwrap(), DIFFICULTY);
+
+        serde_json::json!({
+            "challenge": challenge["challenge"],
+            "tag": challenge["tag"],
+            "solution": solution,
+            "transfer": {
+                "token": self.native_token.to_string(),
+                "target": target.to_string(),
+                "amount": amount,
+            },
+        })
+    }
+
+    /// Replaces the access list through the admin API.
+    pub async fn set_access_list(&self, access_list: Value) {
+        let (status, body) = self
+            .request(
+                Method::PUT,
+                "/api/v1/admin/access-list",
+                Some(access_list),
+                &[("x-admin-key", ADMIN_KEY)],
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+    }
+
+    /// Issues `key` with no restrictions, `configure` narrows it down.
+    pub async fn add_api_key(&self, key: &str, configure: impl FnOnce(&mut ApiKey)) {
+        let mut api_key = ApiKey {
+            name: key.to_string(),
+            key_hash: ApiKey::hash(key),
+            daily_quota: None,
+            allowed_tokens: None,
+            max_amount: None,
+            allow_custom_memo: false,
+        };
+        configure(&mut api_key);
+
+        self.db
+            .write()
+            .await
+            .api_keys
+            .insert(api_key.key_hash.clone(), api_key);
+    }
+
+    /// Request body for a transfer authenticated with an API key, without a
+    /// challenge.
+    pub fn keyed_request(&self, target: &Address, amount: u64) -> Value {
+        serde_json::json!({
+            "transfer": {
+                "token": self.native_token.to_string(),
+                "target": target.to_string(),
+                "amount": amount,
+            },
+        })
+    }
+
+    pub async fn balance(&self, owner: &Address) -> Amount {
+        self.ledger
+            .balance(&self.native_token, owner)
+            .await
+            .unwrap()
+    }
+}
+
+/// Sends a JSON request to `router` as if it came from a local client.
+pub async fn send(
+    router: &Router,
+    method: Method,
+    uri: &str,
+    body: Option<Value>,
+    headers: &[(&str, &str)],
+) -> (StatusCode, Value) {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    for (name, value) in headers {
+        builder = builder.header(*name, *value);
+    }
+
+    let mut request = builder
+        .body(Body::from(
+            body.map(|body| body.to_string()).unwrap_or_default(),
+        ))
+        .unwrap();
+    request
+        .extensions_mut()
+        .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 40000))));
+
+    let response = router.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+pub fn default_settings() -> FaucetSettings {
+    FaucetSettings {
+        difficulty: DIFFICULTY,
+        withdraw_limit: 1_000 * 10_u64.pow(6),
+        tokens: HashMap::new(),
+        fees: FeeSettings::default(),
+        memo_template: DEFAULT_MEMO_TEMPLATE.to_string(),
+        campaign: String::new(),
+        allowed_targets: DEFAULT_ALLOWED_TARGETS.into_iter().collect(),
+        target_balance_limit: None,
+        require_ownership_proof: false,
+        reveal_pk: false,
+    }
+}
+
+/// The faucet router with a single account signing with `faucet`, sending
+/// through `ledger`.
+pub fn faucet_router(
+    ledger: Arc<dyn LedgerClient>,
+    faucet: &common::PublicKey,
+    settings: &Arc<RwLock<FaucetSettings>>,
+    db: &Arc<RwLock<AppState>>,
+    rps: u64,
+    dry_run: bool,
+) -> Router {
+    pool_router(
+        ledger,
+        vec![FaucetAccount::new("faucet".to_string(), faucet.clone())],
+        settings,
+        db,
+        rps,
+        dry_run,
+    )
+}
+
+/// The faucet router dispatching round robin across `accounts`.
+pub fn pool_router(
+    ledger: Arc<dyn LedgerClient>,
+    accounts: Vec<FaucetAccount>,
+    settings: &Arc<RwLock<FaucetSettings>>,
+    db: &Arc<RwLock<AppState>>,
+    rps: u64,
+    dry_run: bool,
+) -> Router {
+    let accounts = AccountPool::new(accounts, DispatchStrategy::RoundRobin);
+
+    let state = FaucetState::new(
+        db,
+        accounts,
+        None,
+        ledger,
+        AUTH_KEY.to_string(),
+        Some(ADMIN_KEY.to_string()),
+        None,
+        settings,
+        "test-chain".to_string(),
+        0,
+        dry_run,
+    );
+
+    ApplicationServer::router(state, RateLimiter::new(rps))
+}
+
+pub fn secret_key() -> common::SecretKey {
+    common::SecretKey::Ed25519(ed25519::SigScheme::generate(&mut OsRng))
+}
+
+pub fn implicit_address() -> Address {
+    Address::from(&secret_key().ref_to())
+}
+
+/// Brute forces a solution to `challenge`.
+pub fn solve(challenge: &str, difficulty: u64) -> String {
+    let challenge = challenge.to_string();
+    (0_u64..)
+        .map(|nonce| HEXLOWER.encode(&nonce.to_be_bytes()))
+        .find(|solution| is_valid_proof_of_work(&challenge, solution, difficulty))
+        .unwrap()
+}
+
+/// A solution that doesn't satisfy `difficulty` for `challenge`.
+pub fn wrong_solution(challenge: &str, difficulty: u64) -> String {
+    let challenge = challenge.to_string();
+    (0_u64..)
+        .map(|nonce| HEXLOWER.encode(&nonce.to_be_bytes()))
+        .find(|solution| !is_valid_proof_of_work(&challenge, solution, difficulty))
+        .unwrap()
+}
+
+/// A fresh directory for tests that need one on disk.
+pub fn temp_dir(name: &str) -> std::path::PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("namada-faucet-{}-{}", name, nanos));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,177 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use axum::{extract::State, routing::post, Json, Router};
+use data_encoding::{BASE64, HEXUPPER};
+use namada_sdk::{
+    address::Address,
+    borsh::BorshSerializeExt,
+    chain::BlockHeight,
+    events::{
+        extend::{ComposeEvent, Height, Info, Log, TxHash},
+        Event, EventLevel,
+    },
+    gas::{event::GasUsed, WholeGas},
+    hash::Hash,
+    storage::Key,
+    token::{Amount, Denomination},
+    tx::{
+        data::ResultCode,
+        event::{types::APPLIED, Code},
+    },
+};
+use orion::hazardous::hash::sha2::sha256::Sha256;
+use serde_json::{json, Value};
+
+/// Every token has the native token's denomination.
+const DENOMINATION: u8 = 6;
+
+/// Minimal CometBFT JSON-RPC server answering the ABCI queries the faucet
+/// makes for the native token, balances, denominations and storage values
+/// set by the test, and accepting broadcasts. Every transaction asked about
+/// is reported as applied at height 1.
+#[derive(Clone)]
+pub struct MockRpc {
+    pub native_token: Address,
+    balances: Arc<Mutex<HashMap<String, Amount>>>,
+    storage: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+    broadcasts: Arc<Mutex<Vec<Vec<u8>>>>,
+}
+
+impl MockRpc {
+    pub fn new(native_token: Address) -> Self {
+        Self {
+            native_token,
+            balances: Arc::new(Mutex::new(HashMap::new())),
+            storage: Arc::new(Mutex::new(HashMap::new())),
+            broadcasts: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Balances are looked up by owner only, whatever the token.
+    pub fn set_balance(&self, owner: &Address, amount: Amount) {
+        self.balances
+            .lock()
+            .unwrap()
+            .insert(owner.to_string(), amount);
+    }
+
+    /// Raw value returned for storage reads of `key`.
+    pub fn set_storage(&self, key: &Key, value: Vec<u8>) {
+        self.storage.lock().unwrap().insert(key.to_string(), value);
+    }
+
+    pub fn broadcasts(&self) -> Vec<Vec<u8>> {
+        self.broadcasts.lock().unwrap().clone()
+    }
+
+    /// Serves the mock on a random local port and returns its URL.
+    pub async fn spawn(&self) -> String {
+        let router = Router::new()
+            .route("/", post(Self::handle))
+            .with_state(self.clone());
+
+        let server = axum::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0)))
+            .serve(router.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        format!("http://{}", addr)
+    }
+
+    async fn handle(State(rpc): State<MockRpc>, Json(request): Json<Value>) -> Json<Value> {
+        let result = match request["method"].as_str().unwrap_or_default() {
+            "abci_query" => rpc.abci_query(request["params"]["path"].as_str().unwrap_or_default()),
+            "broadcast_tx_sync" | "broadcast_tx_async" => {
+                let tx = BASE64
+                    .decode(
+                        request["params"]["tx"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .as_bytes(),
+                    )
+                    .unwrap_or_default();
+                let hash = Sha256::digest(&tx).unwrap();
+                rpc.broadcasts.lock().unwrap().push(tx);
+
+                json!({
+                    "code": 0,
+                    "data": "",
+                    "log": "",
+                    "codespace": "",
+                    "hash": HEXUPPER.encode(hash.as_ref()),
+                })
+            }
+            method => {
+                return Json(json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "error": { "code": -32601, "message": format!("{} not supported", method) },
+                }))
+            }
+        };
+
+        Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+    }
+
+    fn abci_query(&self, path: &str) -> Value {
+        let stored = path
+            .strip_prefix("/shell/value/")
+            .and_then(|key| self.storage.lock().unwrap().get(key).cloned());
+
+        let value = if stored.is_some() {
+            stored
+        } else if let Some(hash) = path.strip_prefix("/shell/applied/") {
+            Some(Self::applied_event(hash).serialize_to_vec())
+        } else if path == "/shell/native_token" {
+            Some(self.native_token.serialize_to_vec())
+        } else if path.starts_with("/vp/token/denomination/") {
+            Some(Some(Denomination(DENOMINATION)).serialize_to_vec())
+        } else if path.contains("balance") {
+            // Both the token VP query and raw storage reads of the balance
+            // key end with the owner address.
+            let owner = path.rsplit('/').next().unwrap_or_default();
+            let owner = owner.trim_start_matches('#');
+            let balance = self.balances.lock().unwrap().get(owner).copied();
+            Some(balance.unwrap_or_default().serialize_to_vec())
+        } else {
+            None
+        };
+
+        let (code, value) = match value {
+            Some(value) => (0, value),
+            None => (1, Vec::new()),
+        };
+
+        json!({
+            "response": {
+                "code": code,
+                "log": "",
+                "info": "",
+                "index": "0",
+                "key": "",
+                "value": BASE64.encode(&value),
+                "proofOps": null,
+                "height": "1",
+                "codespace": "",
+            }
+        })
+    }
+
+    fn applied_event(hash: &str) -> Option<Event> {
+        let hash = Hash::from_str(hash).ok()?;
+        let event = Event::new(APPLIED, EventLevel::Tx)
+            .with(TxHash(hash))
+            .with(Height(BlockHeight(1)))
+            .with(Code(ResultCode::Ok))
+            .with(GasUsed(WholeGas::from(0)))
+            .with(Info(String::new()))
+            .with(Log(String::new()))
+            .into();
+        Some(event)
+    }
+}
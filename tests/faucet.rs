@@ -0,0 +1,753 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use axum::http::{Method, StatusCode};
+use common::{
+    implicit_address, pool_router, secret_key, wrong_solution, TestApp, ADMIN_KEY, DIFFICULTY, FEE,
+};
+use data_encoding::HEXLOWER;
+use namada_faucet::{
+    config::{TargetBalanceLimit, TargetBalancePolicy},
+    sdk::ledger::TxStatus,
+    state::pool::FaucetAccount,
+};
+use namada_sdk::{
+    address::Address,
+    key::{common, ed25519, RefTo},
+    token::Amount,
+};
+use serde_json::Value;
+
+#[tokio::test]
+async fn settings_default_to_the_native_token() {
+    let app = TestApp::new();
+
+    let (status, body) = app.get("/api/v1/faucet/setting").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["difficulty"], DIFFICULTY);
+    assert_eq!(body["chain_id"], "test-chain");
+    assert_eq!(
+        body["tokens_alias_to_address"]["NAM"],
+        app.native_token.to_string()
+    );
+}
+
+#[tokio::test]
+async fn challenge_pow_and_transfer() {
+    let app = TestApp::new();
+    let target = implicit_address();
+
+    let request = app.solved_request(&target, 1_000_000).await;
+    let (status, body) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["sent"], true);
+    assert_eq!(body["amount"], 1_000_000);
+    assert!(body["tx_hash"].is_string());
+    assert!(body["request_id"].is_string());
+    assert_eq!(app.balance(&target).await, Amount::from(1_000_000));
+    assert_eq!(
+        app.balance(&app.faucet).await,
+        Amount::from(common::FAUCET_BALANCE - 1_000_000 - FEE)
+    );
+}
+
+#[tokio::test]
+async fn challenges_cant_be_replayed() {
+    let app = TestApp::new();
+    let target = implicit_address();
+
+    let request = app.solved_request(&target, 1_000).await;
+    let (status, _) = app.post("/api/v1/faucet", request.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = app.post("/api/v1/faucet", request).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(app.balance(&target).await, Amount::from(1_000));
+}
+
+#[tokio::test]
+async fn wrong_solution_is_rejected() {
+    let app = TestApp::new();
+
+    let mut request = app.solved_request(&implicit_address(), 1_000).await;
+    request["solution"] = wrong_solution(request["challenge"].as_str().unwrap(), DIFFICULTY).into();
+    let (status, _) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn forged_tag_is_rejected() {
+    let app = TestApp::new();
+
+    let mut request = app.solved_request(&implicit_address(), 1_000).await;
+    request["tag"] = "00".repeat(32).into();
+    let (status, _) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn missing_challenge_is_rejected() {
+    let app = TestApp::new();
+
+    let mut request = app.solved_request(&implicit_address(), 1_000).await;
+    request.as_object_mut().unwrap().remove("challenge");
+    let (status, _) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn withdraw_limit_is_enforced() {
+    let app = TestApp::new();
+
+    let request = app
+        .solved_request(&implicit_address(), 1_001 * 10_u64.pow(6))
+        .await;
+    let (status, body) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["message"].as_str().unwrap().contains("Withdraw limit"));
+}
+
+#[tokio::test]
+async fn faucet_out_of_balance() {
+    let app = TestApp::new();
+    app.ledger
+        .set_balance(&app.native_token, &app.faucet, Amount::from(10));
+
+    let request = app.solved_request(&implicit_address(), 1_000).await;
+    let (status, _) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(app.ledger.transactions().is_empty());
+}
+
+#[tokio::test]
+async fn balance_check_counts_the_reveal_pk_fee() {
+    let app = TestApp::new();
+    app.settings.write().await.reveal_pk = true;
+    app.ledger
+        .set_balance(&app.native_token, &app.faucet, Amount::from(1_000 + FEE));
+    let key = secret_key();
+    let target = Address::from(&key.ref_to());
+
+    let mut request = app.solved_request(&target, 1_000).await;
+    request["public_key"] = key.ref_to().to_string().into();
+    let (status, _) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(app.ledger.transactions().is_empty());
+}
+
+#[tokio::test]
+async fn well_funded_targets_are_refused() {
+    let app = TestApp::new();
+    app.settings.write().await.target_balance_limit = Some(TargetBalanceLimit {
+        threshold: 1,
+        policy: TargetBalancePolicy::Refuse,
+    });
+    let target = implicit_address();
+    app.ledger
+        .set_balance(&app.native_token, &target, Amount::from(1_000_000));
+
+    let request = app.solved_request(&target, 1_000).await;
+    let (status, body) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::CONFLICT, "{}", body);
+    assert!(app.ledger.transactions().is_empty());
+}
+
+#[tokio::test]
+async fn capped_transfers_only_top_up_to_the_threshold() {
+    let app = TestApp::new();
+    app.settings.write().await.target_balance_limit = Some(TargetBalanceLimit {
+        threshold: 1,
+        policy: TargetBalancePolicy::Cap,
+    });
+    let target = implicit_address();
+    app.ledger
+        .set_balance(&app.native_token, &target, Amount::from(400_000));
+
+    let request = app.solved_request(&target, 1_000_000).await;
+    let (status, body) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["amount"], 600_000);
+    assert_eq!(app.balance(&target).await, Amount::from(1_000_000));
+}
+
+#[tokio::test]
+async fn faucet_accounts_cant_be_targeted() {
+    let app = TestApp::new();
+
+    let request = app.solved_request(&app.faucet, 1_000).await;
+    let (status, _) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn invalid_body_is_rejected() {
+    let app = TestApp::new();
+
+    let (status, _) = app
+        .post(
+            "/api/v1/faucet",
+            serde_json::json!({ "transfer": { "token": "", "target": "", "amount": 0 } }),
+        )
+        .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn admin_routes_require_the_admin_key() {
+    let app = TestApp::new();
+
+    let (status, _) = app.get("/api/v1/admin/accounts").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, body) = app
+        .request(
+            Method::GET,
+            "/api/v1/admin/accounts",
+            None,
+            &[("x-admin-key", ADMIN_KEY)],
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body[0]["address"], app.faucet.to_string());
+}
+
+#[tokio::test]
+async fn idempotent_requests_are_sent_once() {
+    let app = TestApp::new();
+    let target = implicit_address();
+    let headers = [("idempotency-key", "retry-me")];
+
+    let request = app.solved_request(&target, 1_000).await;
+    let (status, first) = app
+        .request(
+            Method::POST,
+            "/api/v1/faucet",
+            Some(request.clone()),
+            &headers,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, second) = app
+        .request(Method::POST, "/api/v1/faucet", Some(request), &headers)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(first, second);
+    assert_eq!(app.ledger.transactions().len(), 1);
+    assert_eq!(app.balance(&target).await, Amount::from(1_000));
+}
+
+#[tokio::test]
+async fn idempotency_keys_cant_be_reused_for_other_requests() {
+    let app = TestApp::new();
+    let headers = [("idempotency-key", "reused")];
+
+    let request = app.solved_request(&implicit_address(), 1_000).await;
+    let (status, _) = app
+        .request(Method::POST, "/api/v1/faucet", Some(request), &headers)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let request = app.solved_request(&implicit_address(), 1_000).await;
+    let (status, _) = app
+        .request(Method::POST, "/api/v1/faucet", Some(request), &headers)
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn concurrent_requests_with_one_challenge_send_once() {
+    let app = TestApp::new();
+    let target = implicit_address();
+
+    let request = app.solved_request(&target, 1_000).await;
+    let (first, second) = tokio::join!(
+        app.post("/api/v1/faucet", request.clone()),
+        app.post("/api/v1/faucet", request),
+    );
+
+    let mut statuses = vec![first.0, second.0];
+    statuses.sort();
+    assert_eq!(statuses, vec![StatusCode::OK, StatusCode::CONFLICT]);
+    assert_eq!(app.balance(&target).await, Amount::from(1_000));
+}
+
+#[tokio::test]
+async fn transfer_events_are_replayed() {
+    let app = TestApp::new();
+
+    let request = app.solved_request(&implicit_address(), 1_000).await;
+    let (_, body) = app.post("/api/v1/faucet", request).await;
+    let request_id = body["request_id"].as_str().unwrap();
+
+    let (status, events) = app
+        .get_text(&format!("/api/v1/faucet/events/{}", request_id))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    assert_eq!(
+        event_statuses(&events),
+        vec!["queued", "broadcast", "applied"]
+    );
+    assert!(events.contains(body["tx_hash"].as_str().unwrap()));
+}
+
+/// `status` of every event in a server-sent event stream.
+fn event_statuses(events: &str) -> Vec<Value> {
+    events
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .map(|data| serde_json::from_str::<Value>(data).unwrap()["status"].clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn transfer_events_can_be_followed_before_the_request_is_sent() {
+    let app = TestApp::new();
+    app.ledger.hold(true);
+
+    let (status, events) = app
+        .stream_text("/api/v1/faucet/events?idempotency_key=first-request")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let request = app.solved_request(&implicit_address(), 1_000).await;
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/v1/faucet",
+            Some(request),
+            &[("idempotency-key", "first-request")],
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["sent"], false);
+
+    // The transfer is applied after the faucet answered, the stream follows
+    // it live until then.
+    let tx_hash = body["tx_hash"].as_str().unwrap();
+    app.ledger.settle(tx_hash, TxStatus::Applied).unwrap();
+    let events = tokio::time::timeout(Duration::from_secs(10), events)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        event_statuses(&events),
+        vec!["queued", "broadcast", "applied"]
+    );
+    assert!(events.contains(body["request_id"].as_str().unwrap()));
+}
+
+#[tokio::test]
+async fn events_of_unknown_requests_are_not_found() {
+    let app = TestApp::new();
+
+    let (status, _) = app.get("/api/v1/faucet/events/unknown").await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn openapi_document_lists_the_faucet_routes() {
+    let app = TestApp::new();
+
+    let (status, body) = app.get("/api/v1/openapi.json").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["paths"]["/api/v1/faucet"]["post"].is_object());
+    assert!(body["components"]["schemas"]["FaucetRequestDto"].is_object());
+}
+
+#[tokio::test]
+async fn unknown_routes_are_not_found() {
+    let app = TestApp::new();
+
+    let (status, _) = app.get("/api/v1/unknown").await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn requests_over_the_rate_limit_wait_for_the_next_window() {
+    let start = Instant::now();
+    let app = TestApp::with_rps(2);
+
+    for _ in 0..3 {
+        let (status, _) = app.get("/api/v1/faucet/setting").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    assert!(start.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn blocked_ips_are_refused_challenges_and_transfers() {
+    let app = TestApp::new();
+    let target = implicit_address();
+    let request = app.solved_request(&target, 1_000).await;
+
+    app.set_access_list(serde_json::json!({ "deny": { "ips": ["127.0.0.1/32"] } }))
+        .await;
+
+    let (status, body) = app.get("/api/v1/faucet").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body["message"].as_str().unwrap().contains("blocked"));
+
+    let (status, body) = app.post("/api/v1/faucet", request).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body["message"].as_str().unwrap().contains("blocked"));
+    assert!(app.ledger.transactions().is_empty());
+}
+
+#[tokio::test]
+async fn blocked_addresses_are_refused_transfers() {
+    let app = TestApp::new();
+    let target = implicit_address();
+    app.set_access_list(serde_json::json!({ "deny": { "addresses": [target.to_string()] } }))
+        .await;
+
+    // Challenges aren't tied to an address, so only the transfer is refused.
+    let request = app.solved_request(&target, 1_000).await;
+    let (status, body) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body["message"].as_str().unwrap().contains("blocked"));
+    assert!(app.ledger.transactions().is_empty());
+}
+
+#[tokio::test]
+async fn deny_wins_over_allow() {
+    let app = TestApp::new();
+    let target = implicit_address();
+    let withdraw_limit = app.settings.read().await.withdraw_limit;
+
+    // Allowed addresses may go over the withdraw limit.
+    app.set_access_list(serde_json::json!({ "allow": { "addresses": [target.to_string()] } }))
+        .await;
+    let request = app.solved_request(&target, withdraw_limit + 1).await;
+    let (status, body) = app.post("/api/v1/faucet", request).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    app.set_access_list(serde_json::json!({
+        "deny": { "addresses": [target.to_string()] },
+        "allow": { "addresses": [target.to_string()] },
+    }))
+    .await;
+    let request = app.solved_request(&target, withdraw_limit + 1).await;
+    let (status, body) = app.post("/api/v1/faucet", request).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body["message"].as_str().unwrap().contains("blocked"));
+    assert_eq!(app.ledger.transactions().len(), 1);
+}
+
+#[tokio::test]
+async fn api_keys_skip_the_proof_of_work() {
+    let app = TestApp::new();
+    app.add_api_key("integrator", |_| {}).await;
+    let target = implicit_address();
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/v1/faucet",
+            Some(app.keyed_request(&target, 1_000)),
+            &[("x-api-key", "integrator")],
+        )
+        .await;
+
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(app.balance(&target).await, Amount::from(1_000));
+}
+
+#[tokio::test]
+async fn unknown_api_keys_are_refused() {
+    let app = TestApp::new();
+    app.add_api_key("integrator", |_| {}).await;
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/v1/faucet",
+            Some(app.keyed_request(&implicit_address(), 1_000)),
+            &[("x-api-key", "forged")],
+        )
+        .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["message"], "Invalid API key");
+    assert!(app.ledger.transactions().is_empty());
+}
+
+#[tokio::test]
+async fn api_keys_are_limited_to_their_daily_quota() {
+    let app = TestApp::new();
+    app.add_api_key("integrator", |api_key| api_key.daily_quota = Some(1))
+        .await;
+    let target = implicit_address();
+    let headers = [("x-api-key", "integrator")];
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/v1/faucet",
+            Some(app.keyed_request(&target, 1_000)),
+            &headers,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/v1/faucet",
+            Some(app.keyed_request(&target, 1_000)),
+            &headers,
+        )
+        .await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert!(body["message"].as_str().unwrap().contains("quota"));
+    assert_eq!(app.ledger.transactions().len(), 1);
+}
+
+#[tokio::test]
+async fn api_keys_are_limited_to_their_tokens() {
+    let app = TestApp::new();
+    let other_token = implicit_address().to_string();
+    app.add_api_key("integrator", |api_key| {
+        api_key.allowed_tokens = Some([other_token].into_iter().collect())
+    })
+    .await;
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/v1/faucet",
+            Some(app.keyed_request(&implicit_address(), 1_000)),
+            &[("x-api-key", "integrator")],
+        )
+        .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body["message"].as_str().unwrap().contains("not allowed"));
+    assert!(app.ledger.transactions().is_empty());
+}
+
+#[tokio::test]
+async fn api_keys_are_limited_to_their_max_amount() {
+    let app = TestApp::new();
+    app.add_api_key("integrator", |api_key| api_key.max_amount = Some(1))
+        .await;
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/v1/faucet",
+            Some(app.keyed_request(&implicit_address(), 1_000_001)),
+            &[("x-api-key", "integrator")],
+        )
+        .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["message"].as_str().unwrap().contains("1000000"));
+    assert!(app.ledger.transactions().is_empty());
+}
+
+#[tokio::test]
+async fn memos_are_rendered_from_the_template() {
+    let app = TestApp::new();
+    {
+        let mut settings = app.settings.write().await;
+        settings.memo_template = "{campaign} on {chain_id}: {amount} to {target}".to_string();
+        settings.campaign = "launch".to_string();
+    }
+    let target = implicit_address();
+
+    let request = app.solved_request(&target, 1_000).await;
+    let (status, body) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(
+        app.ledger.memo(body["tx_hash"].as_str().unwrap()).unwrap(),
+        format!("launch on test-chain: 1000 to {}", target)
+    );
+}
+
+#[tokio::test]
+async fn custom_memos_need_an_api_key_that_allows_them() {
+    let app = TestApp::new();
+    app.add_api_key("plain", |_| {}).await;
+    app.add_api_key("memo", |api_key| api_key.allow_custom_memo = true)
+        .await;
+    let target = implicit_address();
+
+    let mut request = app.solved_request(&target, 1_000).await;
+    request["memo"] = "hello".into();
+    let (status, body) = app.post("/api/v1/faucet", request).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body["message"].as_str().unwrap().contains("memo"));
+
+    let mut request = app.keyed_request(&target, 1_000);
+    request["memo"] = "hello".into();
+    let (status, _) = app
+        .request(
+            Method::POST,
+            "/api/v1/faucet",
+            Some(request.clone()),
+            &[("x-api-key", "plain")],
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(app.ledger.transactions().is_empty());
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/v1/faucet",
+            Some(request),
+            &[("x-api-key", "memo")],
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(
+        app.ledger.memo(body["tx_hash"].as_str().unwrap()).unwrap(),
+        "hello"
+    );
+}
+
+/// Signs the raw bytes of `challenge` the way wallets prove ownership.
+fn sign_challenge(secret_key: &common::SecretKey, challenge: &Value) -> String {
+    let challenge = HEXLOWER
+        .decode(challenge.as_str().unwrap().as_bytes())
+        .unwrap();
+    let common::SecretKey::Ed25519(secret_key) = secret_key else {
+        unreachable!("test keys are ed25519")
+    };
+    common::Signature::Ed25519(ed25519::Signature(secret_key.0.sign(&challenge))).to_string()
+}
+
+async fn ownership_app() -> TestApp {
+    let app = TestApp::new();
+    app.settings.write().await.require_ownership_proof = true;
+    app
+}
+
+#[tokio::test]
+async fn ownership_is_proven_by_signing_the_challenge() {
+    let app = ownership_app().await;
+    let owner = secret_key();
+    let target = Address::from(&owner.ref_to());
+
+    let mut request = app.solved_request(&target, 1_000).await;
+    request["public_key"] = owner.ref_to().to_string().into();
+    request["signature"] = sign_challenge(&owner, &request["challenge"]).into();
+    let (status, body) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(app.balance(&target).await, Amount::from(1_000));
+}
+
+#[tokio::test]
+async fn ownership_proofs_signed_by_another_key_are_refused() {
+    let app = ownership_app().await;
+    let owner = secret_key();
+    let target = Address::from(&owner.ref_to());
+
+    let mut request = app.solved_request(&target, 1_000).await;
+    request["public_key"] = owner.ref_to().to_string().into();
+    request["signature"] = sign_challenge(&secret_key(), &request["challenge"]).into();
+    let (status, body) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body["message"].as_str().unwrap().contains("ownership"));
+    assert!(app.ledger.transactions().is_empty());
+}
+
+#[tokio::test]
+async fn ownership_proofs_without_a_signature_are_refused() {
+    let app = ownership_app().await;
+    let owner = secret_key();
+    let target = Address::from(&owner.ref_to());
+
+    let mut request = app.solved_request(&target, 1_000).await;
+    request["public_key"] = owner.ref_to().to_string().into();
+    let (status, body) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body["message"].as_str().unwrap().contains("ownership"));
+    assert!(app.ledger.transactions().is_empty());
+}
+
+/// Routes `app` through a pool of `balances.len()` fresh accounts holding
+/// `balances`, returning their addresses.
+fn with_pool(app: &mut TestApp, balances: &[u64]) -> Vec<Address> {
+    let accounts: Vec<_> = balances
+        .iter()
+        .enumerate()
+        .map(|(index, balance)| {
+            let account = FaucetAccount::new(format!("pool-{}", index), secret_key().ref_to());
+            app.ledger
+                .set_balance(&app.native_token, &account.address, Amount::from(*balance));
+            account
+        })
+        .collect();
+    let addresses = accounts
+        .iter()
+        .map(|account| account.address.clone())
+        .collect();
+
+    app.router = pool_router(
+        app.ledger.clone(),
+        accounts,
+        &app.settings,
+        &app.db,
+        u64::MAX,
+        false,
+    );
+    addresses
+}
+
+#[tokio::test]
+async fn underfunded_pool_accounts_are_skipped() {
+    let mut app = TestApp::new();
+    let accounts = with_pool(&mut app, &[0, 1_000_000]);
+    let target = implicit_address();
+
+    for _ in 0..2 {
+        let request = app.solved_request(&target, 1_000).await;
+        let (status, body) = app.post("/api/v1/faucet", request).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+    }
+
+    assert_eq!(app.balance(&target).await, Amount::from(2_000));
+    assert_eq!(app.balance(&accounts[0]).await, Amount::zero());
+    assert_eq!(
+        app.balance(&accounts[1]).await,
+        Amount::from(1_000_000 - 2 * (1_000 + FEE))
+    );
+}
+
+#[tokio::test]
+async fn transfers_fail_when_every_pool_account_is_underfunded() {
+    let mut app = TestApp::new();
+    with_pool(&mut app, &[0, 1_000]);
+
+    let request = app.solved_request(&implicit_address(), 1_000).await;
+    let (status, _) = app.post("/api/v1/faucet", request).await;
+
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(app.ledger.transactions().is_empty());
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use std::fs;
+
+use common::{secret_key, temp_dir};
+use namada_faucet::sdk::key::{KeySource, Keystore};
+
+#[test]
+fn keystore_round_trips_the_secret_key() {
+    let sk = secret_key().to_string();
+    let keystore = Keystore::encrypt(&sk, "correct horse").unwrap();
+
+    assert_eq!(*keystore.decrypt("correct horse").unwrap(), sk);
+}
+
+#[test]
+fn keystore_rejects_a_wrong_password() {
+    let keystore = Keystore::encrypt(&secret_key().to_string(), "correct horse").unwrap();
+
+    let error = keystore.decrypt("battery staple").unwrap_err();
+    assert_eq!(error.to_string(), "wrong keystore password");
+}
+
+#[test]
+fn keystore_source_loads_the_key() {
+    let sk = secret_key();
+    let dir = temp_dir("keystore");
+    let path = dir.join("keystore.json");
+    let password_file = dir.join("password");
+    let keystore = Keystore::encrypt(&sk.to_string(), "correct horse").unwrap();
+    fs::write(&path, serde_json::to_string(&keystore).unwrap()).unwrap();
+    fs::write(&password_file, "correct horse\n").unwrap();
+
+    let source = KeySource::Keystore {
+        path,
+        password_file,
+    };
+
+    assert_eq!(source.load().unwrap(), sk);
+}
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use data_encoding::HEXLOWER;
+use namada_faucet::{
+    app_state::AppState,
+    services::faucet::FaucetService,
+    utils::pow::{is_valid_proof_of_work, leading_zero_nibbles},
+};
+use orion::auth;
+use proptest::prelude::*;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+/// Known answers shared with the client solvers, see `tests/vectors`.
+#[derive(Deserialize)]
+struct PowVector {
+    challenge: String,
+    solution: String,
+    difficulty: u64,
+    valid: bool,
+}
+
+#[derive(Deserialize)]
+struct TagVector {
+    auth_key: String,
+    challenge: String,
+    tag: String,
+    valid: bool,
+}
+
+fn faucet_service() -> FaucetService {
+    FaucetService::new(&Arc::new(RwLock::new(AppState::default())))
+}
+
+#[test]
+fn pow_vectors() {
+    let vectors: Vec<PowVector> = serde_json::from_str(include_str!("vectors/pow.json")).unwrap();
+
+    for vector in vectors {
+        assert_eq!(
+            is_valid_proof_of_work(&vector.challenge, &vector.solution, vector.difficulty),
+            vector.valid,
+            "{} {} at difficulty {}",
+            vector.challenge,
+            vector.solution,
+            vector.difficulty
+        );
+    }
+}
+
+#[test]
+fn tag_vectors() {
+    let vectors: Vec<TagVector> = serde_json::from_str(include_str!("vectors/tag.json")).unwrap();
+    let service = faucet_service();
+
+    for vector in vectors {
+        assert_eq!(
+            service.verify_tag(&vector.auth_key, &vector.challenge, &vector.tag),
+            vector.valid,
+            "{} {}",
+            vector.challenge,
+            vector.tag
+        );
+    }
+}
+
+#[test]
+fn leading_zero_nibbles_counts_half_bytes() {
+    assert_eq!(leading_zero_nibbles(&[]), 0);
+    assert_eq!(leading_zero_nibbles(&[0x10]), 0);
+    assert_eq!(leading_zero_nibbles(&[0x01]), 1);
+    assert_eq!(leading_zero_nibbles(&[0x00, 0x0f]), 3);
+    assert_eq!(leading_zero_nibbles(&[0x00; 32]), 64);
+}
+
+proptest! {
+    #[test]
+    fn valid_solutions_stay_valid_at_lower_difficulty(
+        challenge in prop::array::uniform16(any::<u8>()),
+        difficulty in 1_u64..4,
+    ) {
+        let challenge = HEXLOWER.encode(&challenge);
+        let solution = (0_u64..)
+            .map(|nonce| HEXLOWER.encode(&nonce.to_be_bytes()))
+            .find(|solution| is_valid_proof_of_work(&challenge, solution, difficulty))
+            .unwrap();
+
+        for lower in 0..difficulty {
+            prop_assert!(is_valid_proof_of_work(&challenge, &solution, lower));
+        }
+    }
+
+    #[test]
+    fn any_solution_is_valid_at_zero_difficulty(
+        challenge in prop::collection::vec(any::<u8>(), 0..64),
+        solution in prop::collection::vec(any::<u8>(), 0..64),
+    ) {
+        prop_assert!(is_valid_proof_of_work(
+            &HEXLOWER.encode(&challenge),
+            &HEXLOWER.encode(&solution),
+            0
+        ));
+    }
+
+    #[test]
+    fn malformed_pow_input_never_panics(
+        challenge in ".*",
+        solution in ".*",
+        difficulty in any::<u64>(),
+    ) {
+        is_valid_proof_of_work(&challenge, &solution, difficulty);
+    }
+
+    #[test]
+    fn malformed_tag_input_never_panics(
+        auth_key in ".*",
+        challenge in ".*",
+        tag in ".*",
+    ) {
+        faucet_service().verify_tag(&auth_key, &challenge, &tag);
+    }
+
+    #[test]
+    fn tags_only_verify_their_own_challenge(
+        challenge in prop::array::uniform16(any::<u8>()),
+        other in prop::array::uniform16(any::<u8>()),
+    ) {
+        prop_assume!(challenge != other);
+        let auth_key = "0123456789abcdef0123456789abcdef".to_string();
+        let key = auth::SecretKey::from_slice(auth_key.as_bytes()).unwrap();
+        let tag = auth::authenticate(&key, &challenge).unwrap();
+        let tag = HEXLOWER.encode(tag.unprotected_as_bytes());
+        let service = faucet_service();
+
+        prop_assert!(service.verify_tag(&auth_key, &HEXLOWER.encode(&challenge), &tag));
+        prop_assert!(!service.verify_tag(&auth_key, &HEXLOWER.encode(&other), &tag));
+    }
+}
@@ -0,0 +1,157 @@
+mod common;
+
+use std::{fs, path::Path, sync::Arc};
+
+use clap::Parser;
+use common::{secret_key, temp_dir};
+use namada_faucet::{
+    app_state::AppState,
+    config::{AppConfig, Config},
+    entity::access_list::AccessDecision,
+    reload::ConfigReloader,
+    repository::access_list::{AccessListRepository, AccessListRepositoryTrait},
+    state::faucet::FaucetSettings,
+    utils::rate_limit::RateLimiter,
+};
+use tokio::sync::RwLock;
+
+struct Reload {
+    reloader: ConfigReloader,
+    settings: Arc<RwLock<FaucetSettings>>,
+    access_list_repo: AccessListRepository,
+}
+
+fn write_config(path: &Path, private_key: &str, extra: &str) {
+    let content = format!(
+        "difficulty = 1\n\
+         private_key = \"{}\"\n\
+         chain_start = 0\n\
+         chain_id = \"test-chain\"\n\
+         rpc = \"http://127.0.0.1:26657\"\n\
+         {}",
+        private_key, extra
+    );
+    fs::write(path, content).unwrap();
+}
+
+/// Starts a reloader from the config file at `path`, the way the server does.
+async fn start(path: &Path) -> Reload {
+    let args = AppConfig::parse_from(["namada-faucet", "--config", path.to_str().unwrap()]);
+    let config: Config = args.load().unwrap();
+
+    let settings = Arc::new(RwLock::new(FaucetSettings::from(&config)));
+    let data = Arc::new(RwLock::new(AppState::default()));
+    let mut access_list_repo = AccessListRepository::new(&data);
+    access_list_repo.set(config.access_list.clone()).await;
+    let reloader = ConfigReloader::new(config, &settings, &data, &RateLimiter::new(u64::MAX));
+
+    Reload {
+        reloader,
+        settings,
+        access_list_repo,
+    }
+}
+
+#[tokio::test]
+async fn valid_reloads_swap_the_settings() {
+    let dir = temp_dir("reload");
+    let path = dir.join("config.toml");
+    let private_key = secret_key().to_string();
+    write_config(&path, &private_key, "");
+    let reload = start(&path).await;
+
+    write_config(
+        &path,
+        &private_key,
+        "withdraw_limit = 5\ncampaign = \"launch\"\n",
+    );
+    reload.reloader.reload().await;
+
+    let settings = reload.settings.read().await;
+    assert_eq!(settings.withdraw_limit, 5 * 10_u64.pow(6));
+    assert_eq!(settings.campaign, "launch");
+}
+
+#[tokio::test]
+async fn invalid_reloads_keep_the_old_settings() {
+    let dir = temp_dir("reload");
+    let path = dir.join("config.toml");
+    let private_key = secret_key().to_string();
+    write_config(&path, &private_key, "withdraw_limit = 5\n");
+    let reload = start(&path).await;
+
+    // The valid withdraw limit isn't applied either, the reload is all or nothing.
+    write_config(
+        &path,
+        &private_key,
+        "withdraw_limit = 7\ndifficulty_level = 2\n",
+    );
+    reload.reloader.reload().await;
+    write_config(&path, &private_key, "withdraw_limit = 7\nrps = 0\n");
+    reload.reloader.reload().await;
+
+    let settings = reload.settings.read().await;
+    assert_eq!(settings.withdraw_limit, 5 * 10_u64.pow(6));
+}
+
+#[tokio::test]
+async fn structural_changes_are_ignored() {
+    let dir = temp_dir("reload");
+    let path = dir.join("config.toml");
+    let private_key = secret_key().to_string();
+    fs::write(
+        dir.join("old.json"),
+        r#"{ "deny": { "addresses": ["old"] } }"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join("new.json"),
+        r#"{ "deny": { "addresses": ["new"] } }"#,
+    )
+    .unwrap();
+    let access_list =
+        |name: &str| format!("access_list = \"{}\"\n", dir.join(name).to_str().unwrap());
+    write_config(&path, &private_key, &access_list("old.json"));
+    let reload = start(&path).await;
+
+    write_config(
+        &path,
+        &secret_key().to_string(),
+        &format!(
+            "port = 6000\nwithdraw_limit = 5\n{}",
+            access_list("new.json")
+        ),
+    );
+    reload.reloader.reload().await;
+
+    // The rest of the reload still goes through.
+    assert_eq!(
+        reload.settings.read().await.withdraw_limit,
+        5 * 10_u64.pow(6)
+    );
+    let repo = &reload.access_list_repo;
+    assert_eq!(repo.check(Some("old"), None).await, AccessDecision::Denied);
+    assert_eq!(repo.check(Some("new"), None).await, AccessDecision::Default);
+}
+
+#[tokio::test]
+async fn access_lists_without_a_file_survive_reloads() {
+    let dir = temp_dir("reload");
+    let path = dir.join("config.toml");
+    let private_key = secret_key().to_string();
+    write_config(&path, &private_key, "");
+    let mut reload = start(&path).await;
+
+    // Lists without a file are only edited through the admin API.
+    let mut access_list = reload.access_list_repo.get().await;
+    access_list.deny.addresses.insert("blocked".to_string());
+    reload.access_list_repo.set(access_list).await;
+
+    write_config(&path, &private_key, "withdraw_limit = 5\n");
+    reload.reloader.reload().await;
+
+    assert_eq!(
+        reload.access_list_repo.check(Some("blocked"), None).await,
+        AccessDecision::Denied
+    );
+}
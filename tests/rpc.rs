@@ -0,0 +1,199 @@
+mod common;
+
+use std::{collections::BTreeMap, str::FromStr, sync::Arc};
+
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use common::{
+    default_settings, faucet_router, implicit_address, rpc::MockRpc, secret_key, send, solve,
+    temp_dir, DIFFICULTY, FAUCET_BALANCE,
+};
+use namada_faucet::{
+    app_state::AppState,
+    sdk::{ledger::LedgerClient, namada::NamadaClient},
+};
+use namada_sdk::{
+    address::Address,
+    borsh::BorshSerializeExt,
+    hash::Hash,
+    io::NullIo,
+    key::{common::SecretKey, RefTo},
+    masp::fs::FsShieldedUtils,
+    parameters::storage::get_gas_cost_key,
+    storage::Key,
+    token::Amount,
+    tx::{Section, Tx, TX_TRANSFER_WASM},
+    wallet::fs::FsWalletUtils,
+    NamadaImpl,
+};
+use serde_json::{json, Value};
+use tendermint_rpc::{Client, HttpClient, Url};
+use tokio::sync::RwLock;
+
+/// A client for the node at `url` whose wallet holds `keys`.
+async fn namada_client(url: &str, keys: &[SecretKey]) -> NamadaClient {
+    let client = HttpClient::new(Url::from_str(url).unwrap()).unwrap();
+    let dir = temp_dir("rpc");
+    let sdk = NamadaImpl::new(
+        client,
+        FsWalletUtils::new(dir.join("wallet")),
+        FsShieldedUtils::new(dir.join("masp")),
+        NullIo,
+    )
+    .await
+    .unwrap();
+
+    let mut wallet = sdk.wallet.write().await;
+    for (index, sk) in keys.iter().enumerate() {
+        let address = Address::from(&sk.ref_to());
+        wallet
+            .insert_keypair(
+                format!("key-{}", index),
+                true,
+                sk.clone(),
+                None,
+                Some(address),
+                None,
+            )
+            .unwrap();
+    }
+    drop(wallet);
+
+    NamadaClient::new(Arc::new(sdk), None)
+}
+
+#[tokio::test]
+async fn queries_the_native_token() {
+    let rpc = MockRpc::new(implicit_address());
+    let url = rpc.spawn().await;
+
+    let ledger = namada_client(&url, &[]).await;
+
+    assert_eq!(ledger.native_token().await.unwrap(), rpc.native_token);
+}
+
+#[tokio::test]
+async fn queries_balances() {
+    let rpc = MockRpc::new(implicit_address());
+    let owner = implicit_address();
+    rpc.set_balance(&owner, Amount::from(42));
+    let url = rpc.spawn().await;
+
+    let ledger = namada_client(&url, &[]).await;
+
+    assert_eq!(
+        ledger.balance(&rpc.native_token, &owner).await.unwrap(),
+        Amount::from(42)
+    );
+    assert_eq!(
+        ledger
+            .balance(&rpc.native_token, &implicit_address())
+            .await
+            .unwrap(),
+        Amount::zero()
+    );
+}
+
+#[tokio::test]
+async fn accepts_broadcasts() {
+    let rpc = MockRpc::new(implicit_address());
+    let url = rpc.spawn().await;
+    let client = HttpClient::new(Url::from_str(&url).unwrap()).unwrap();
+
+    let response = client.broadcast_tx_sync(vec![1, 2, 3]).await.unwrap();
+
+    assert!(response.code.is_ok());
+    assert_eq!(rpc.broadcasts(), vec![vec![1, 2, 3]]);
+}
+
+/// A node holding everything the faucet needs to build a transfer signed by
+/// the returned key.
+fn transfer_rpc() -> (MockRpc, SecretKey) {
+    let rpc = MockRpc::new(implicit_address());
+    let faucet_key = secret_key();
+    rpc.set_balance(
+        &Address::from(&faucet_key.ref_to()),
+        Amount::from(FAUCET_BALANCE),
+    );
+    rpc.set_storage(
+        &get_gas_cost_key(),
+        BTreeMap::from([(rpc.native_token.clone(), Amount::from(1))]).serialize_to_vec(),
+    );
+    rpc.set_storage(
+        &Key::wasm_hash(TX_TRANSFER_WASM),
+        Hash::sha256(TX_TRANSFER_WASM.as_bytes()).serialize_to_vec(),
+    );
+    (rpc, faucet_key)
+}
+
+/// Solves a challenge from `router` and requests a native token transfer.
+async fn request_transfer(router: &Router, rpc: &MockRpc) -> (StatusCode, Value) {
+    let (status, challenge) = send(router, Method::GET, "/api/v1/faucet", None, &[]).await;
+    assert_eq!(status, StatusCode::OK);
+    let request = json!({
+        "challenge": challenge["challenge"],
+        "tag": challenge["tag"],
+        "solution": solve(challenge["challenge"].as_str().unwrap(), DIFFICULTY),
+        "transfer": {
+            "token": rpc.native_token.to_string(),
+            "target": implicit_address().to_string(),
+            "amount": 1_000_000,
+        },
+    });
+    send(router, Method::POST, "/api/v1/faucet", Some(request), &[]).await
+}
+
+async fn transfer_router(url: &str, faucet_key: &SecretKey, dry_run: bool) -> Router {
+    let ledger = namada_client(url, &[faucet_key.clone()]).await;
+    faucet_router(
+        Arc::new(ledger),
+        &faucet_key.ref_to(),
+        &Arc::new(RwLock::new(default_settings())),
+        &Arc::new(RwLock::new(AppState::default())),
+        u64::MAX,
+        dry_run,
+    )
+}
+
+#[tokio::test]
+async fn faucet_builds_transfers_through_the_namada_client() {
+    let (rpc, faucet_key) = transfer_rpc();
+    let url = rpc.spawn().await;
+
+    // In dry run mode the transfer is built and signed through the node but
+    // never broadcast.
+    let router = transfer_router(&url, &faucet_key, true).await;
+    let (status, body) = request_transfer(&router, &rpc).await;
+
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["dry_run"], true);
+    assert!(body["tx_hash"].is_string());
+    assert!(body["fee_estimate"].is_string());
+    assert!(rpc.broadcasts().is_empty());
+}
+
+#[tokio::test]
+async fn faucet_broadcasts_transfers_through_the_namada_client() {
+    let (rpc, faucet_key) = transfer_rpc();
+    let url = rpc.spawn().await;
+
+    let router = transfer_router(&url, &faucet_key, false).await;
+    let (status, body) = request_transfer(&router, &rpc).await;
+
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["sent"], true);
+    assert_eq!(body["dry_run"], false);
+
+    // The node got the signed transfer, and the faucet reports its hash.
+    let broadcasts = rpc.broadcasts();
+    assert_eq!(broadcasts.len(), 1);
+    let tx = Tx::try_from(broadcasts[0].as_slice()).unwrap();
+    assert!(tx
+        .sections
+        .iter()
+        .any(|section| matches!(section, Section::Authorization(_))));
+    assert!(tx.memo().is_some());
+    assert_eq!(body["tx_hash"], tx.header_hash().to_string());
+}